@@ -0,0 +1,140 @@
+//! Module to compare two token streams, e.g. to see what a change of the
+//! lexer changed on a corpus.
+
+use crate::LToken;
+
+/// Difference between two token streams, as returned by [`token_diff`].
+#[derive(Debug, PartialEq)]
+#[expect(clippy::exhaustive_enums, reason = "closed set of edits")]
+pub enum TokenDiff<'tokens, 'filepath> {
+    /// Token only present in the new stream.
+    Added(&'tokens LToken<'filepath>),
+    /// Token of the old stream replaced by a different one in the new stream.
+    Changed {
+        /// Token of the new stream.
+        new: &'tokens LToken<'filepath>,
+        /// Token of the old stream.
+        old: &'tokens LToken<'filepath>,
+    },
+    /// Token only present in the old stream.
+    Removed(&'tokens LToken<'filepath>),
+}
+
+/// Computes the differences to go from the `old` token stream to the `new`
+/// one.
+///
+/// The differences are based on a longest common subsequence, and are
+/// returned in the order of the streams. If `include_spans` is false, two
+/// tokens are equal when they have the same content, regardless of where they
+/// are.
+///
+/// The common prefix and suffix of the streams are skipped first, then the
+/// subsequence takes a time and a memory proportional to the product of the
+/// lengths of what remains: small edits of big streams are cheap, but two
+/// unrelated streams of `n` tokens need `n * n` steps.
+#[inline]
+#[must_use]
+pub fn token_diff<'tokens, 'filepath>(
+    old: &'tokens [LToken<'filepath>],
+    new: &'tokens [LToken<'filepath>],
+    include_spans: bool,
+) -> Vec<TokenDiff<'tokens, 'filepath>> {
+    let same = |first: &LToken<'_>, second: &LToken<'_>| {
+        first.token == second.token && (!include_spans || first.span == second.span)
+    };
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|&(first, second)| same(first, second))
+        .count();
+    let (old_rest, new_rest) =
+        (old.get(prefix..).unwrap_or_default(), new.get(prefix..).unwrap_or_default());
+    let suffix = old_rest
+        .iter()
+        .rev()
+        .zip(new_rest.iter().rev())
+        .take_while(|&(first, second)| same(first, second))
+        .count();
+    let old_edit = old_rest
+        .get(..old_rest.len().saturating_sub(suffix))
+        .unwrap_or_default();
+    let new_edit = new_rest
+        .get(..new_rest.len().saturating_sub(suffix))
+        .unwrap_or_default();
+    subsequence_diff(old_edit, new_edit, same)
+}
+
+/// Computes the differences to go from `old` to `new` with their longest
+/// common subsequence, where `same` tells if two tokens are equal, see
+/// [`token_diff`].
+fn subsequence_diff<'tokens, 'filepath, F>(
+    old: &'tokens [LToken<'filepath>],
+    new: &'tokens [LToken<'filepath>],
+    same: F,
+) -> Vec<TokenDiff<'tokens, 'filepath>>
+where
+    F: Fn(&LToken<'_>, &LToken<'_>) -> bool,
+{
+    // `lengths[i * width + j]` is the length of the longest common subsequence
+    // of `old[i..]` and `new[j..]`.
+    let width = new.len().saturating_add(1);
+    let cell = |i: usize, j: usize| i.saturating_mul(width).saturating_add(j);
+    let mut lengths: Vec<usize> = vec![0; old.len().saturating_add(1).saturating_mul(width)];
+    for (i, first) in old.iter().enumerate().rev() {
+        for (j, second) in new.iter().enumerate().rev() {
+            let length = if same(first, second) {
+                lengths
+                    .get(cell(i.saturating_add(1), j.saturating_add(1)))
+                    .map_or(0, |length| length.saturating_add(1))
+            } else {
+                let skip_old = lengths
+                    .get(cell(i.saturating_add(1), j))
+                    .copied()
+                    .unwrap_or_default();
+                let skip_new = lengths
+                    .get(cell(i, j.saturating_add(1)))
+                    .copied()
+                    .unwrap_or_default();
+                skip_old.max(skip_new)
+            };
+            if let Some(target) = lengths.get_mut(cell(i, j)) {
+                *target = length;
+            }
+        }
+    }
+    let length = |i: usize, j: usize| lengths.get(cell(i, j)).copied().unwrap_or_default();
+
+    let mut diffs = vec![];
+    let (mut i, mut j) = (0, 0);
+    loop {
+        match (old.get(i), new.get(j)) {
+            (None, None) => break,
+            (Some(first), Some(second)) if same(first, second) => {
+                i = i.saturating_add(1);
+                j = j.saturating_add(1);
+            }
+            (Some(first), Some(second))
+                if length(i.saturating_add(1), j.saturating_add(1)) == length(i, j) =>
+            {
+                diffs.push(TokenDiff::Changed { new: second, old: first });
+                i = i.saturating_add(1);
+                j = j.saturating_add(1);
+            }
+            (Some(first), Some(_))
+                if length(i.saturating_add(1), j) >= length(i, j.saturating_add(1)) =>
+            {
+                diffs.push(TokenDiff::Removed(first));
+                i = i.saturating_add(1);
+            }
+            (Some(first), None) => {
+                diffs.push(TokenDiff::Removed(first));
+                i = i.saturating_add(1);
+            }
+            (_, Some(second)) => {
+                diffs.push(TokenDiff::Added(second));
+                j = j.saturating_add(1);
+            }
+        }
+    }
+    diffs
+}
@@ -0,0 +1,97 @@
+//! Module to define the [`LexError`] type, and its owned version.
+
+use core::{error, fmt};
+
+use crate::{OwnedTokenSpan, TokenSpan};
+
+/// Error returned when the source can't be lexed.
+#[derive(Debug, PartialEq, Eq)]
+#[expect(
+    clippy::exhaustive_structs,
+    reason = "errors are a kind, a message and a span"
+)]
+pub struct LexError<'filepath> {
+    /// Kind of the error, to handle it without reading the message.
+    pub kind: LexErrorKind,
+    /// Description of the error, without the location.
+    pub message: String,
+    /// Span of the source that caused the error.
+    pub span: TokenSpan<'filepath>,
+}
+
+impl<'filepath> LexError<'filepath> {
+    /// Makes an error for the given span, of kind [`LexErrorKind::Syntax`].
+    #[inline]
+    #[must_use]
+    pub fn new<T: Into<String>>(message: T, span: TokenSpan<'filepath>) -> Self {
+        Self { kind: LexErrorKind::Syntax, message: message.into(), span }
+    }
+
+    /// Changes the kind of the error.
+    #[inline]
+    #[must_use]
+    pub fn with_kind(self, kind: LexErrorKind) -> Self {
+        Self { kind, ..self }
+    }
+}
+
+#[expect(clippy::missing_trait_methods, reason = "the error has no source")]
+impl error::Error for LexError<'_> {}
+
+impl fmt::Display for LexError<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = self.span.start.human();
+        if let Some(filepath) = self.span.filepath {
+            write!(f, "{}:", filepath.display())?;
+        }
+        write!(f, "{line}:{col}: {}", self.message)
+    }
+}
+
+/// Same as [`LexError`], but owns the path of its file, to outlive it, see
+/// [`spawn_lexer`](crate::spawn_lexer).
+#[derive(Debug, PartialEq, Eq)]
+#[expect(clippy::exhaustive_structs, reason = "same fields as LexError")]
+pub struct OwnedLexError {
+    /// Kind of the error, see [`LexError`].
+    pub kind: LexErrorKind,
+    /// Description of the error, without the location.
+    pub message: String,
+    /// Span of the source that caused the error.
+    pub span: OwnedTokenSpan,
+}
+
+impl From<LexError<'_>> for OwnedLexError {
+    #[inline]
+    fn from(error: LexError<'_>) -> Self {
+        Self { kind: error.kind, message: error.message, span: error.span.into() }
+    }
+}
+
+#[expect(clippy::missing_trait_methods, reason = "the error has no source")]
+impl error::Error for OwnedLexError {}
+
+impl fmt::Display for OwnedLexError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = self.span.start.human();
+        if let Some(filepath) = &self.span.filepath {
+            write!(f, "{}:", filepath.display())?;
+        }
+        write!(f, "{line}:{col}: {}", self.message)
+    }
+}
+
+/// Kind of a [`LexError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LexErrorKind {
+    /// More characters than allowed were lexed, see
+    /// [`lex_with_budget`](crate::lex_with_budget).
+    BudgetExceeded,
+    /// The file can't be read, see [`lex_file`](crate::lex_file).
+    Io,
+    /// The source is invalid.
+    Syntax,
+}
@@ -0,0 +1,54 @@
+//! Module to detect the indentation style of a source file.
+
+use alloc::collections::BTreeMap;
+use core::cmp::Reverse;
+
+/// Indentation unit of a source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "closed set of styles")]
+pub enum IndentStyle {
+    /// Lines are indented with the given number of spaces per level.
+    Spaces(usize),
+    /// Lines are indented with tabs.
+    Tabs,
+    /// No line is indented.
+    Unknown,
+}
+
+/// Detects the dominant indentation unit of `source`.
+///
+/// Tabs win if more lines start with a tab than with spaces. Otherwise, the
+/// unit is the most common difference of indentation between two consecutive
+/// lines indented with spaces, the smallest one in case of a tie.
+#[inline]
+#[must_use]
+pub fn detect_indent(source: &str) -> IndentStyle {
+    let mut tabs: usize = 0;
+    let mut spaces: usize = 0;
+    let mut deltas = BTreeMap::<usize, usize>::new();
+    let mut previous: usize = 0;
+    for line in source.lines().filter(|line| !line.trim().is_empty()) {
+        if line.starts_with('\t') {
+            tabs = tabs.saturating_add(1);
+            continue;
+        }
+        let width = line.bytes().take_while(|byte| *byte == b' ').count();
+        if width != 0 {
+            spaces = spaces.saturating_add(1);
+        }
+        let delta = width.abs_diff(previous);
+        if delta != 0 {
+            let count = deltas.entry(delta).or_default();
+            *count = count.saturating_add(1);
+        }
+        previous = width;
+    }
+    if tabs > spaces {
+        IndentStyle::Tabs
+    } else {
+        deltas
+            .into_iter()
+            .max_by_key(|&(delta, count)| (count, Reverse(delta)))
+            .map_or(IndentStyle::Unknown, |(delta, _)| IndentStyle::Spaces(delta))
+    }
+}
@@ -2,20 +2,49 @@
 
 use core::fmt;
 
+use crate::standard::Standard;
+
 /// Defines the keyword type and its methods
 macro_rules! impl_keywords {
-    ($($pascal:ident $type:ident $str:expr ,)*) => {
+    ($($pascal:ident $type:ident $standard:ident $str:expr ,)* ; $($gnu_pascal:ident $gnu_type:ident $gnu_str:expr ,)*) => {
 
         /// Keywords of the language
         ///
         /// See [CppReference](https://en.cppreference.com/w/c/keyword) for the list of C keywords.
+        /// The `Gnu` variants are GNU extensions, only recognised by
+        /// [`Keyword::from_gnu_value`].
         #[derive(Debug, PartialEq, Eq)]
+        #[expect(clippy::exhaustive_enums, reason = "keywords are fixed by the standards")]
+        #[expect(
+            clippy::arbitrary_source_item_ordering,
+            reason = "GNU extensions come after the standard keywords"
+        )]
         pub enum Keyword {
-            $($pascal,)*
+            $(#[doc = concat!("`", $str, "`")] $pascal,)*
+            $(#[doc = concat!("`", $gnu_str, "`")] $gnu_pascal,)*
         }
 
         impl Keyword {
+            /// Tries to make a keyword from a literal, for the given C
+            /// standard.
+            ///
+            /// Keywords introduced after `standard` are not recognised, and
+            /// keywords are only reported as deprecated from C23 onwards.
+            #[inline]
+            pub fn from_value_for(value: &str, standard: Standard) -> TryKeyword {
+                match value {
+                    $($str if Standard::$standard <= standard =>
+                        if standard < Standard::C23 {
+                            TryKeyword::Success(Self::$pascal)
+                        } else {
+                            TryKeyword::from(Self::$pascal)
+                        },)*
+                    _ => TryKeyword::Failure,
+                }
+            }
+
             /// Tries to make a keyword from a literal.
+            #[inline]
             pub fn from_value_or_res(value: &str) -> TryKeyword {
                 match value {
                     $($str => TryKeyword::from(Self::$pascal),)*
@@ -24,9 +53,22 @@ macro_rules! impl_keywords {
             }
 
             /// Returns the type of a keyword.
+            #[inline]
             pub const fn keyword_type(&self) -> KeywordType {
                 match self {
                     $(Self::$pascal => KeywordType::$type,)*
+                    $(Self::$gnu_pascal => KeywordType::$gnu_type,)*
+                }
+            }
+
+            /// Returns the first C standard in which the keyword exists.
+            ///
+            /// GNU extensions are available in every standard.
+            #[inline]
+            pub const fn standard(&self) -> Standard {
+                match self {
+                    $(Self::$pascal => Standard::$standard,)*
+                    $(Self::$gnu_pascal => Standard::C89,)*
                 }
             }
 
@@ -34,9 +76,11 @@ macro_rules! impl_keywords {
 
         #[expect(clippy::min_ident_chars)]
         impl fmt::Display for Keyword {
+            #[inline]
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 match self {
                     $(Self::$pascal => $str.fmt(f),)*
+                    $(Self::$gnu_pascal => $gnu_str.fmt(f),)*
                 }
             }
         }
@@ -44,75 +88,108 @@ macro_rules! impl_keywords {
 }
 
 impl_keywords!(
-    Alignof Operator "alignof",
-    Alignas Storage "alignas",
-    Auto Storage "auto",
-    Bool Type "bool",
-    Break Control "break",
-    Case Control "case",
-    Char Type "char",
-    Const Storage "const",
-    Constexpr Storage "constexpr",
-    Continue Control "continue",
-    Default Control "default",
-    Do Control "do",
-    Double Type "double",
-    Else Control "else",
-    Enum Type "enum",
-    Extern Storage "extern",
-    False Literal "false",
-    Float Type "float",
-    For Control "for",
-    Goto Control "goto",
-    If Control "if",
-    Inline Storage "inline",
-    Int Type "int",
-    Long Type "long",
-    Null Literal "NULL",
-    Nullptr Literal "nullptr",
-    Register Storage "register",
-    Restrict Storage "restrict",
-    Return Control "return",
-    Short Type "short",
-    Signed Type "signed",
-    Sizeof Operator "sizeof",
-    Static Storage "static",
-    StaticAssert Control "static_assert",
-    Struct Type "struct",
-    Switch Control "switch",
-    ThreadLocal Storage "thread_local",
-    True Literal "true",
-    Typedef Storage "typedef",
-    Typeof Operator "typeof",
-    TypeofUnqual Operator "typeof_unqual",
-    Union Type "union",
-    Unsigned Type "unsigned",
-    Void Type "void",
-    Volatile Storage "volatile",
-    While Control "while",
-    UAlignas Storage "_Alignas",
-    UAlignof Operator "_Alignof",
-    UAtomic Storage "_Atomic",
-    UBigInt Type "_BigInt",
-    UBool Type "_Bool",
-    UComplex Type "_Complex",
-    UDecimal128 Type "_Decimal128",
-    UDecimal32 Type "_Decimal32",
-    UDecimal64 Type "_Decimal64",
-    UGeneric Operator "_Generic",
-    UImaginary Type "_Imaginary",
-    UNoreturn Storage "_Noreturn",
-    UStaticAssert Control "_Static_assert",
-    UThreadLocal Storage "_Thread_local",
+    Alignof Operator C23 "alignof",
+    Alignas Storage C23 "alignas",
+    Auto Storage C89 "auto",
+    Bool Type C23 "bool",
+    Break Control C89 "break",
+    Case Control C89 "case",
+    Char Type C89 "char",
+    Const Storage C89 "const",
+    Constexpr Storage C23 "constexpr",
+    Continue Control C89 "continue",
+    Default Control C89 "default",
+    Do Control C89 "do",
+    Double Type C89 "double",
+    Else Control C89 "else",
+    Enum Type C89 "enum",
+    Extern Storage C89 "extern",
+    False Literal C23 "false",
+    Float Type C89 "float",
+    For Control C89 "for",
+    Goto Control C89 "goto",
+    If Control C89 "if",
+    Inline Storage C99 "inline",
+    Int Type C89 "int",
+    Long Type C89 "long",
+    Nullptr Literal C23 "nullptr",
+    Register Storage C89 "register",
+    Restrict Storage C99 "restrict",
+    Return Control C89 "return",
+    Short Type C89 "short",
+    Signed Type C89 "signed",
+    Sizeof Operator C89 "sizeof",
+    Static Storage C89 "static",
+    StaticAssert Control C23 "static_assert",
+    Struct Type C89 "struct",
+    Switch Control C89 "switch",
+    ThreadLocal Storage C23 "thread_local",
+    True Literal C23 "true",
+    Typedef Storage C89 "typedef",
+    Typeof Operator C23 "typeof",
+    TypeofUnqual Operator C23 "typeof_unqual",
+    Union Type C89 "union",
+    Unsigned Type C89 "unsigned",
+    Void Type C89 "void",
+    Volatile Storage C89 "volatile",
+    While Control C89 "while",
+    UAlignas Storage C11 "_Alignas",
+    UAlignof Operator C11 "_Alignof",
+    UAtomic Storage C11 "_Atomic",
+    UBitInt Type C23 "_BitInt",
+    UBool Type C99 "_Bool",
+    UComplex Type C99 "_Complex",
+    UDecimal128 Type C23 "_Decimal128",
+    UDecimal32 Type C23 "_Decimal32",
+    UDecimal64 Type C23 "_Decimal64",
+    UGeneric Operator C11 "_Generic",
+    UImaginary Type C99 "_Imaginary",
+    UNoreturn Storage C11 "_Noreturn",
+    UStaticAssert Control C11 "_Static_assert",
+    UThreadLocal Storage C11 "_Thread_local",
+    ;
+    GnuAsm Control "__asm__",
+    GnuAttribute Storage "__attribute__",
+    GnuExtension Storage "__extension__",
 );
 
+impl Keyword {
+    /// Tries to make a keyword from a literal, with GNU extensions enabled.
+    ///
+    /// On top of the keywords of `standard`, this recognises the GNU-only
+    /// keywords (e.g. `__extension__`) and the double-underscore spellings of
+    /// standard keywords (e.g. `__inline__` for `inline`), whatever the
+    /// standard.
+    #[inline]
+    #[must_use]
+    pub fn from_gnu_value(value: &str, standard: Standard) -> TryKeyword {
+        let keyword = match value {
+            "__alignof" | "__alignof__" => Self::Alignof,
+            "__asm" | "__asm__" => Self::GnuAsm,
+            "__attribute" | "__attribute__" => Self::GnuAttribute,
+            "__complex" | "__complex__" => Self::UComplex,
+            "__const" | "__const__" => Self::Const,
+            "__extension__" => Self::GnuExtension,
+            "__inline" | "__inline__" => Self::Inline,
+            "__restrict" | "__restrict__" => Self::Restrict,
+            "__signed" | "__signed__" => Self::Signed,
+            "__thread" => Self::UThreadLocal,
+            "__typeof" | "__typeof__" => Self::Typeof,
+            "__volatile" | "__volatile__" => Self::Volatile,
+            _ => return Self::from_value_for(value, standard),
+        };
+        TryKeyword::Success(keyword)
+    }
+}
+
 /// Type of keywords
 #[derive(Debug, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "closed set of categories")]
 pub enum KeywordType {
     /// Control flow keywords, like `while`, `for`, `case`, `break`. Each
     /// control flow keyword has a specific syntax.
     Control,
-    /// Constant keywords, like `true` or `NULL`
+    /// Constant keywords, like `true` or `nullptr`
     Literal,
     /// Operator keywords. These keyword functions, like `sizeof` or `alignof`.
     Operator,
@@ -129,6 +206,7 @@ pub enum KeywordType {
 /// # Note
 ///
 /// For the moment, deprecated means deprecated for C23.
+#[expect(clippy::exhaustive_enums, reason = "closed set of outcomes")]
 pub enum TryKeyword {
     /// Is a keyword, but deprecated for C23
     Deprecated(Keyword),
@@ -139,6 +217,7 @@ pub enum TryKeyword {
 }
 
 impl From<Keyword> for TryKeyword {
+    #[inline]
     fn from(keyword: Keyword) -> Self {
         if matches!(keyword, |Keyword::UAlignas| Keyword::UAlignof
             | Keyword::UBool
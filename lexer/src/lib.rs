@@ -45,23 +45,46 @@
 #![expect(clippy::blanket_clippy_restriction_lints, reason = "I want them all")]
 #![expect(clippy::multiple_inherent_impl, reason = "useful when lots of methods")]
 
-mod keyword;
-mod literal;
-mod location;
-mod operator;
-mod symbol;
-mod token;
+extern crate alloc;
 
+pub mod diff;
+pub mod error;
+pub mod indent;
+pub mod keyword;
+pub mod literal;
+pub mod location;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod operator;
+pub mod reserved;
+pub mod standard;
+pub mod symbol;
+pub mod token;
+
+use alloc::collections::BTreeMap;
 use core::str::Lines;
-use std::path::Path;
+use core::{fmt, iter, mem};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
 
-use literal::Literal;
-use location::Location;
+use error::{LexError, LexErrorKind, OwnedLexError};
+use keyword::{Keyword, TryKeyword};
+use literal::{Encoding, Literal};
+use location::{ColumnUnit, Location};
 use operator::Operator;
+use standard::Standard;
+use symbol::SymbolTable;
 use token::Token;
 
+/// Characters that end a line, alone or in `\r\n`.
+const LINE_ENDS: [char; 2] = ['\n', '\r'];
+
 /// Represents the location of a token, to allow clear error messages
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct TokenSpan<'filepath> {
     pub filepath: Option<&'filepath Path>,
     /// first character of the span
@@ -76,14 +99,121 @@ impl<'filepath, 'b: 'filepath> From<(&'filepath Path, &'b Location)> for TokenSp
     }
 }
 
+impl TokenSpan<'_> {
+    /// Returns the number of bytes of the spanned text in `source`.
+    ///
+    /// `source` must be the content of the file the span refers to. Returns 0
+    /// if the span is not in `source`.
+    #[inline]
+    #[must_use]
+    pub fn byte_len(&self, source: &str) -> usize {
+        let start = byte_offset(source, self.start.line(), self.start.col());
+        let end = byte_offset(source, self.end.line(), self.end.col().saturating_add(1));
+        start
+            .zip(end)
+            .map_or(0, |(first, last)| last.saturating_sub(first))
+    }
+
+    /// Returns the path of the file of the span, for display.
+    ///
+    /// With `forward_slashes`, the `\` separators of Windows paths are
+    /// replaced by `/`, whatever the platform, for portable diagnostics.
+    #[inline]
+    #[must_use]
+    pub fn display_path(&self, forward_slashes: bool) -> Option<String> {
+        let path = self.filepath?.display().to_string();
+        Some(if forward_slashes {
+            path.replace('\\', "/")
+        } else {
+            path
+        })
+    }
+
+    /// Returns the smallest span covering both `self` and `other`.
+    ///
+    /// The file of `self` is kept.
+    #[inline]
+    #[must_use]
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            filepath: self.filepath,
+            start: self.start.clone().min(other.start.clone()),
+            end: self.end.clone().max(other.end.clone()),
+        }
+    }
+}
+
 /// LocalizedToken is a Token with localisation information
+#[derive(Debug, PartialEq)]
 pub struct LToken<'filepath> {
+    /// identifier of the token, unique and increasing in the stream it was
+    /// lexed in, whatever its span.
+    pub id: usize,
+    /// the token is part of a preprocessor directive, like the expression of
+    /// an `#if`.
+    pub preprocessor: bool,
     /// token span that covers the token
     pub span: TokenSpan<'filepath>,
     /// actual token
     pub token: Token,
 }
 
+impl fmt::Display for LToken<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = self.span.start.human();
+        write!(f, "{line}:{col}\t{}", self.token)
+    }
+}
+
+/// Same as [`TokenSpan`], but owns the path of its file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[expect(
+    clippy::exhaustive_structs,
+    reason = "spans are a file and two locations"
+)]
+pub struct OwnedTokenSpan {
+    /// Last character of the span.
+    pub end: Location,
+    /// Path of the file of the span.
+    pub filepath: Option<PathBuf>,
+    /// First character of the span.
+    pub start: Location,
+}
+
+impl From<TokenSpan<'_>> for OwnedTokenSpan {
+    #[inline]
+    fn from(span: TokenSpan<'_>) -> Self {
+        Self { end: span.end, filepath: span.filepath.map(Path::to_path_buf), start: span.start }
+    }
+}
+
+/// Same as [`LToken`], but owns the path of its file, see [`lex_file`].
+#[derive(Debug, PartialEq)]
+#[expect(clippy::exhaustive_structs, reason = "same fields as LToken")]
+pub struct OwnedLToken {
+    /// Identifier of the token, see [`LToken`].
+    pub id: usize,
+    /// The token is part of a preprocessor directive, see [`LToken`].
+    pub preprocessor: bool,
+    /// Token span that covers the token.
+    pub span: OwnedTokenSpan,
+    /// Actual token.
+    pub token: Token,
+}
+
+impl From<LToken<'_>> for OwnedLToken {
+    #[inline]
+    fn from(token: LToken<'_>) -> Self {
+        Self {
+            id: token.id,
+            preprocessor: token.preprocessor,
+            span: token.span.into(),
+            token: token.token,
+        }
+    }
+}
+
 /// convenient type for a token stream
 pub type Tokens<'filepath> = Vec<LToken<'filepath>>;
 
@@ -101,81 +231,139 @@ enum FlushCounter {
 }
 
 impl OperatorBuilder {
-    fn push(&mut self, ch: char) -> Option<Operator> {
-        let mut lexed_operator = None;
-        *self = match self {
-            Self::None => Self::One(ch),
-            Self::One(first) => Self::Two(*first, ch),
-            Self::Two(first, second) => Self::Three(*first, *second, ch),
-            Self::Three(first, second, third) => {
-                let (size, operator) = match (*first, *second, *third) {
-                    ('<', '<', '=') => (FlushCounter::Three, Operator::ShiftLeftAssign),
-                    ('>', '>', '=') => (FlushCounter::Three, Operator::ShiftRightAssign),
-                    ('-', '>', _) => (FlushCounter::Two, Operator::Arrow),
-                    ('+', '+', _) => (FlushCounter::Two, Operator::Increment),
-                    ('-', '-', _) => (FlushCounter::Two, Operator::Decrement),
-                    ('<', '<', _) => (FlushCounter::Two, Operator::ShiftLeft),
-                    ('>', '>', _) => (FlushCounter::Two, Operator::ShiftRight),
-                    ('&', '&', _) => (FlushCounter::Two, Operator::LogicalAnd),
-                    ('|', '|', _) => (FlushCounter::Two, Operator::LogicalOr),
-                    ('<', '=', _) => (FlushCounter::Two, Operator::Le),
-                    ('>', '=', _) => (FlushCounter::Two, Operator::Ge),
-                    ('=', '=', _) => (FlushCounter::Two, Operator::Equal),
-                    ('!', '=', _) => (FlushCounter::Two, Operator::Different),
-                    ('+', '=', _) => (FlushCounter::Two, Operator::AddAssign),
-                    ('-', '=', _) => (FlushCounter::Two, Operator::SubAssign),
-                    ('*', '=', _) => (FlushCounter::Two, Operator::MulAssign),
-                    ('/', '=', _) => (FlushCounter::Two, Operator::DivAssign),
-                    ('%', '=', _) => (FlushCounter::Two, Operator::ModAssign),
-                    ('&', '=', _) => (FlushCounter::Two, Operator::AndAssign),
-                    ('|', '=', _) => (FlushCounter::Two, Operator::OrAssign),
-                    ('^', '=', _) => (FlushCounter::Two, Operator::XorAssign),
-                    ('+', _, _) => (FlushCounter::One, Operator::Plus),
-                    ('-', _, _) => (FlushCounter::One, Operator::Minus),
-                    ('(', _, _) => (FlushCounter::One, Operator::ParenthesisOpen),
-                    (')', _, _) => (FlushCounter::One, Operator::ParenthesisClose),
-                    ('[', _, _) => (FlushCounter::One, Operator::BracketOpen),
-                    (']', _, _) => (FlushCounter::One, Operator::BracketClose),
-                    ('.', _, _) => (FlushCounter::One, Operator::Dot),
-                    ('{', _, _) => (FlushCounter::One, Operator::BraceOpen),
-                    ('}', _, _) => (FlushCounter::One, Operator::BraceClose),
-                    ('~', _, _) => (FlushCounter::One, Operator::BitwiseNot),
-                    ('!', _, _) => (FlushCounter::One, Operator::LogicalNot),
-                    ('*', _, _) => (FlushCounter::One, Operator::Star),
-                    ('&', _, _) => (FlushCounter::One, Operator::Ampersand),
-                    ('%', _, _) => (FlushCounter::One, Operator::Modulo),
-                    ('/', _, _) => (FlushCounter::One, Operator::Divide),
-                    ('>', _, _) => (FlushCounter::One, Operator::Gt),
-                    ('<', _, _) => (FlushCounter::One, Operator::Lt),
-                    ('=', _, _) => (FlushCounter::One, Operator::Assign),
-                    ('|', _, _) => (FlushCounter::One, Operator::BitwiseOr),
-                    ('^', _, _) => (FlushCounter::One, Operator::BitwiseXor),
-                    (',', _, _) => (FlushCounter::One, Operator::Comma),
-                    ('?', _, _) => (FlushCounter::One, Operator::Interrogation),
-                    (':', _, _) => (FlushCounter::One, Operator::Colon),
-                    (';', _, _) => (FlushCounter::One, Operator::SemiColon),
-                    _ => unreachable!(),
-                };
-                lexed_operator = Some(operator);
-                match size {
-                    FlushCounter::One => Self::Two(*second, *third),
-                    FlushCounter::Two => Self::One(*third),
-                    FlushCounter::Three => Self::None,
-                }
+    /// Returns the last buffered character.
+    const fn last(&self) -> Option<char> {
+        match *self {
+            Self::None => None,
+            Self::One(last) | Self::Two(_, last) | Self::Three(_, _, last) => Some(last),
+        }
+    }
+
+    /// Returns the longest operator starting with the given characters, and
+    /// how many of them it uses, or [`None`] if no operator starts with
+    /// `first`.
+    const fn longest(
+        first: char,
+        second: Option<char>,
+        third: Option<char>,
+    ) -> Option<(FlushCounter, Operator)> {
+        match (first, second, third) {
+            ('<', Some('<'), Some('=')) => Some((FlushCounter::Three, Operator::ShiftLeftAssign)),
+            ('>', Some('>'), Some('=')) => Some((FlushCounter::Three, Operator::ShiftRightAssign)),
+            ('.', Some('.'), Some('.')) => Some((FlushCounter::Three, Operator::Ellipsis)),
+            ('-', Some('>'), _) => Some((FlushCounter::Two, Operator::Arrow)),
+            ('+', Some('+'), _) => Some((FlushCounter::Two, Operator::Increment)),
+            ('-', Some('-'), _) => Some((FlushCounter::Two, Operator::Decrement)),
+            ('<', Some('<'), _) => Some((FlushCounter::Two, Operator::ShiftLeft)),
+            ('>', Some('>'), _) => Some((FlushCounter::Two, Operator::ShiftRight)),
+            ('&', Some('&'), _) => Some((FlushCounter::Two, Operator::LogicalAnd)),
+            ('|', Some('|'), _) => Some((FlushCounter::Two, Operator::LogicalOr)),
+            ('<', Some('='), _) => Some((FlushCounter::Two, Operator::Le)),
+            ('>', Some('='), _) => Some((FlushCounter::Two, Operator::Ge)),
+            ('=', Some('='), _) => Some((FlushCounter::Two, Operator::Equal)),
+            ('!', Some('='), _) => Some((FlushCounter::Two, Operator::Different)),
+            ('+', Some('='), _) => Some((FlushCounter::Two, Operator::AddAssign)),
+            ('-', Some('='), _) => Some((FlushCounter::Two, Operator::SubAssign)),
+            ('*', Some('='), _) => Some((FlushCounter::Two, Operator::MulAssign)),
+            ('/', Some('='), _) => Some((FlushCounter::Two, Operator::DivAssign)),
+            ('%', Some('='), _) => Some((FlushCounter::Two, Operator::ModAssign)),
+            ('&', Some('='), _) => Some((FlushCounter::Two, Operator::AndAssign)),
+            ('|', Some('='), _) => Some((FlushCounter::Two, Operator::OrAssign)),
+            ('^', Some('='), _) => Some((FlushCounter::Two, Operator::XorAssign)),
+            ('#', Some('#'), _) => Some((FlushCounter::Two, Operator::HashHash)),
+            ('+', _, _) => Some((FlushCounter::One, Operator::Plus)),
+            ('-', _, _) => Some((FlushCounter::One, Operator::Minus)),
+            ('(', _, _) => Some((FlushCounter::One, Operator::ParenthesisOpen)),
+            (')', _, _) => Some((FlushCounter::One, Operator::ParenthesisClose)),
+            ('[', _, _) => Some((FlushCounter::One, Operator::BracketOpen)),
+            (']', _, _) => Some((FlushCounter::One, Operator::BracketClose)),
+            ('.', _, _) => Some((FlushCounter::One, Operator::Dot)),
+            ('{', _, _) => Some((FlushCounter::One, Operator::BraceOpen)),
+            ('}', _, _) => Some((FlushCounter::One, Operator::BraceClose)),
+            ('~', _, _) => Some((FlushCounter::One, Operator::BitwiseNot)),
+            ('!', _, _) => Some((FlushCounter::One, Operator::LogicalNot)),
+            ('*', _, _) => Some((FlushCounter::One, Operator::Star)),
+            ('&', _, _) => Some((FlushCounter::One, Operator::Ampersand)),
+            ('%', _, _) => Some((FlushCounter::One, Operator::Modulo)),
+            ('/', _, _) => Some((FlushCounter::One, Operator::Divide)),
+            ('>', _, _) => Some((FlushCounter::One, Operator::Gt)),
+            ('<', _, _) => Some((FlushCounter::One, Operator::Lt)),
+            ('=', _, _) => Some((FlushCounter::One, Operator::Assign)),
+            ('|', _, _) => Some((FlushCounter::One, Operator::BitwiseOr)),
+            ('^', _, _) => Some((FlushCounter::One, Operator::BitwiseXor)),
+            (',', _, _) => Some((FlushCounter::One, Operator::Comma)),
+            ('?', _, _) => Some((FlushCounter::One, Operator::Interrogation)),
+            (':', _, _) => Some((FlushCounter::One, Operator::Colon)),
+            (';', _, _) => Some((FlushCounter::One, Operator::SemiColon)),
+            ('#', _, _) => Some((FlushCounter::One, Operator::Hash)),
+            _ => None,
+        }
+    }
+
+    /// Removes the longest operator at the start of the buffer, and returns it
+    /// with its number of characters. Fails with the first character if no
+    /// operator starts with it.
+    fn pop(&mut self) -> Result<Option<(Operator, usize)>, char> {
+        let (size, operator) = match *self {
+            Self::None => return Ok(None),
+            Self::One(first) => Self::longest(first, None, None).ok_or(first)?,
+            Self::Two(first, second) => Self::longest(first, Some(second), None).ok_or(first)?,
+            Self::Three(first, second, third) =>
+                Self::longest(first, Some(second), Some(third)).ok_or(first)?,
+        };
+        let (len, rest) = match (size, &*self) {
+            (FlushCounter::One, Self::Two(_, second)) => (1, Self::One(*second)),
+            (FlushCounter::One, Self::Three(_, second, third)) => (1, Self::Two(*second, *third)),
+            (FlushCounter::Two, Self::Three(_, _, third)) => (2, Self::One(*third)),
+            (FlushCounter::One, _) => (1, Self::None),
+            (FlushCounter::Two, _) => (2, Self::None),
+            (FlushCounter::Three, _) => (3, Self::None),
+        };
+        *self = rest;
+        Ok(Some((operator, len)))
+    }
+
+    /// Adds a character to the buffer. When the buffer is full, the longest
+    /// operator at its start is removed and returned first, see
+    /// [`OperatorBuilder::pop`].
+    fn push(&mut self, ch: char) -> Result<Option<(Operator, usize)>, char> {
+        match *self {
+            Self::None => *self = Self::One(ch),
+            Self::One(first) => *self = Self::Two(first, ch),
+            Self::Two(first, second) => *self = Self::Three(first, second, ch),
+            Self::Three(..) => {
+                let lexed_operator = self.pop()?;
+                self.push(ch)?;
+                return Ok(lexed_operator);
             }
+        }
+        Ok(None)
+    }
+
+    /// Removes the last buffered character.
+    const fn remove_last(&mut self) {
+        *self = match *self {
+            Self::None | Self::One(_) => Self::None,
+            Self::Two(first, _) => Self::One(first),
+            Self::Three(first, second, _) => Self::Two(first, second),
         };
-        lexed_operator
     }
 }
 
 #[derive(Default)]
 enum TokenBuilderContent {
+    /// Block comment. The boolean is set when the last character is a `*`.
+    BlockComment(bool),
+    /// Char literal, with the characters read so far, like `'AB'`.
+    Char(String),
+    /// Preprocessor directive, after the `#`.
+    Directive(String),
     /// Identifier, used when parsing function definitions
     Ident(String),
+    /// Line comment, up to the end of the line.
+    LineComment,
     /// String literal
     String(String),
-    /// Char literal. When the first ' is read, this is None.
-    Char(Option<char>),
     /// Number literal
     Number(String),
     /// Operator, see https://en.cppreference.com/w/c/language/operator_precedence
@@ -185,100 +373,1128 @@ enum TokenBuilderContent {
 }
 
 impl TokenBuilderContent {
-    fn take_token<'filepath>(&mut self) -> Option<Token> {
-        match &self {
-            Self::Ident(string) => todo!(),
-            Self::String(string) => todo!(),
-            Self::Char(Some(char)) => Some(Token::Literal(Literal::Char(*char))),
-            Self::Number(string) => todo!(),
-            Self::Operator(op) => todo!(),
-            _ => None,
+    /// Starts a string literal after `"`, or a char literal otherwise.
+    const fn quoted(quote: char) -> Self {
+        if quote == '"' {
+            Self::String(String::new())
+        } else {
+            Self::Char(String::new())
         }
     }
+
+    /// Takes the token being built, where `span` is its span, `encoding` the
+    /// encoding of a char or string, and `standard` the one whose keywords
+    /// are recognised, with the GNU ones if `gnu` is set.
+    fn take_token<'filepath>(
+        &mut self,
+        span: &TokenSpan<'filepath>,
+        encoding: Encoding,
+        standard: Standard,
+        gnu: bool,
+    ) -> Result<Option<Token>, LexError<'filepath>> {
+        let from_value = if gnu {
+            Keyword::from_gnu_value
+        } else {
+            Keyword::from_value_for
+        };
+        Ok(match mem::take(self) {
+            Self::Ident(string) if string.is_empty() => None,
+            Self::Ident(string) => Some(match from_value(&string, standard) {
+                TryKeyword::Deprecated(keyword) | TryKeyword::Success(keyword) =>
+                    Token::Keyword(keyword),
+                TryKeyword::Failure => Token::Symbol(string),
+            }),
+            Self::String(string) => Some(Token::Literal(Literal::String(string, encoding))),
+            Self::Char(chars) => Some(Token::Literal(
+                Literal::from_chars(&chars, encoding)
+                    .ok_or_else(|| LexError::new("more than one element in char", span.clone()))?,
+            )),
+            Self::Number(string) => Some(Token::Literal(
+                Literal::from_number(&string)
+                    .map_err(|message| LexError::new(message, span.clone()))?,
+            )),
+            // Null directive
+            Self::Directive(name) if name.is_empty() => None,
+            Self::Directive(name) => Some(Token::Directive(name)),
+            // Operators can make several tokens, see `TokenBuilder::flush_operators`
+            _ => None,
+        })
+    }
 }
 
 #[derive(Default)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "independent states of the lexer"
+)]
 struct TokenBuilder<'filepath> {
-    span: TokenSpan<'filepath>,
+    /// The directive is an `#if` or `#elif`, whose expression can use the
+    /// `defined` operator.
+    condition: bool,
     content: TokenBuilderContent,
+    /// Encoding of the char or string being built, given by its prefix.
+    encoding: Encoding,
+    /// Characters after the backslash of the escape sequence being read in a
+    /// char or string.
+    escape: Option<String>,
+    /// Location of the backslash of the escape sequence being read.
+    escape_start: Location,
+    /// The GNU keywords are recognised, see [`Keyword::from_gnu_value`].
+    gnu: bool,
+    /// A token was pushed on the current line, so a `#` is an operator and
+    /// doesn't start a directive.
+    line_started: bool,
+    /// Id of the next token, see [`LToken::id`].
+    next_id: usize,
+    /// The tokens are in a preprocessor directive, up to the end of the line.
+    preprocessor: bool,
+    span: TokenSpan<'filepath>,
+    /// Standard whose keywords are recognised.
+    standard: Standard,
 }
 
 impl<'a, 'filepath: 'a> TokenBuilder<'filepath> {
-    fn push_token(&mut self, tokens: &mut Vec<LToken<'a>>) {
-        let filepath = self.span.filepath;
-        if let Some(token) = self.content.take_token() {
-            tokens.push(LToken { span: std::mem::take(&mut self.span), token });
-            self.span.filepath = filepath;
+    /// Makes an error spanning from `start` to `end`.
+    fn error(&self, message: &str, start: Location, end: Location) -> LexError<'filepath> {
+        LexError::new(message, TokenSpan { filepath: self.span.filepath, start, end })
+    }
+
+    /// Pushes the token being built at the end of the input, including all the
+    /// buffered operators.
+    fn finish(&mut self, tokens: &mut Vec<LToken<'a>>) -> Result<(), LexError<'filepath>> {
+        match self.content {
+            TokenBuilderContent::Operator(_) => self.flush_operators(tokens)?,
+            TokenBuilderContent::Directive(_)
+            | TokenBuilderContent::Ident(_)
+            | TokenBuilderContent::Number(_) => self.push_token(tokens)?,
+            TokenBuilderContent::BlockComment(_) => {
+                let (start, end) = (self.span.start.clone(), self.span.start.clone());
+                return Err(self.error("unterminated block comment", start, end));
+            }
+            TokenBuilderContent::String(_) | TokenBuilderContent::Char(_) =>
+                return Err(self.unterminated()),
+            TokenBuilderContent::LineComment | TokenBuilderContent::None => (),
         }
+        Ok(())
+    }
+
+    /// Pushes all the buffered operators, and resets the builder.
+    fn flush_operators(&mut self, tokens: &mut Vec<LToken<'a>>) -> Result<(), LexError<'filepath>> {
+        if let TokenBuilderContent::Operator(mut op) = mem::take(&mut self.content) {
+            while let Some((operator, len)) = op.pop().map_err(|_ch| self.invalid_operator())? {
+                self.push_operator(tokens, operator, len);
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the error of a buffered character, at the start of the span, that
+    /// starts no operator.
+    fn invalid_operator(&self) -> LexError<'filepath> {
+        let start = self.span.start.clone();
+        self.error("invalid character", start.clone(), start)
     }
 
+    #[expect(clippy::too_many_lines, reason = "one arm per state and character")]
     fn lex_char(
         &mut self,
         tokens: &mut Vec<LToken<'filepath>>,
         ch: char,
         location: Location,
-    ) -> Result<(), String> {
+    ) -> Result<(), LexError<'filepath>> {
         match (ch, &mut self.content) {
-            // Parse char
-            ('\'', TokenBuilderContent::Char(None)) => return Err("missing element in char".into()),
-            (_, TokenBuilderContent::Char(ch_builder @ None)) => {
-                self.span.start = location;
-                *ch_builder = Some(ch)
+            // End of line in an unterminated literal, lines being spliced first
+            ('\n', TokenBuilderContent::Char(_) | TokenBuilderContent::String(_)) =>
+                return Err(self.unterminated()),
+            // Parse escape sequence
+            (_, TokenBuilderContent::Char(_) | TokenBuilderContent::String(_))
+                if self.escape.is_some() =>
+                if !self.lex_escape(ch, location.clone())? {
+                    return self.lex_char(tokens, ch, location);
+                },
+            ('\\', TokenBuilderContent::Char(_) | TokenBuilderContent::String(_)) => {
+                self.escape = Some(String::new());
+                self.escape_start = location;
+            }
+            // Parse char or string
+            ('\'', TokenBuilderContent::Char(chars)) if chars.is_empty() => {
+                let start = self.span.start.clone();
+                return Err(self.error("missing element in char", start, location));
+            }
+            ('\'', TokenBuilderContent::Char(_)) | ('"', TokenBuilderContent::String(_)) => {
+                self.span.end = location;
+                self.push_token(tokens)?;
+            }
+            (_, TokenBuilderContent::Char(string) | TokenBuilderContent::String(string)) =>
+                string.push(ch),
+
+            // Parse directive name
+            (' ' | '\t', TokenBuilderContent::Directive(name)) if name.is_empty() => (),
+            ('0'..='9' | 'a'..='z' | 'A'..='Z' | '_', TokenBuilderContent::Directive(name)) => {
+                name.push(ch);
+                self.span.end = location;
+            }
+
+            // Prefixed char or string, like `L'a'` or `u8"a"`
+            ('\'' | '"', TokenBuilderContent::Ident(prefix))
+                if Encoding::from_prefix(prefix).is_some() =>
+            {
+                self.encoding = Encoding::from_prefix(prefix).unwrap_or_default();
+                self.content = TokenBuilderContent::quoted(ch);
+                self.span.end = location;
             }
-            // Parse string
-            ('\'', TokenBuilderContent::Char(Some(_)))
-            | ('"', TokenBuilderContent::String(_)) => self.push_token(tokens),
-            (_, TokenBuilderContent::Char(Some(_))) =>
-                return Err("more than one element in char".into()),
-            (_, TokenBuilderContent::String(string)) => string.push(ch),
-
-            // Parse number
+
+            // Parse number, where a sign is only part of an exponent, like `1e-5`, and
+            // `'` is a digit separator, like `1'000`
             ('0'..='9' | 'a'..='z' | 'A'..='Z' | '_', TokenBuilderContent::Ident(string))
             | (
-                '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '.' | '+' | '-',
+                '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '.' | '+' | '-' | '\'',
                 TokenBuilderContent::Number(string),
-            ) => string.push(ch),
+            ) if !matches!(ch, '+' | '-') || string.ends_with(['e', 'E', 'p', 'P']) => {
+                string.push(ch);
+                self.span.end = location;
+            }
+
+            // Parse comment
+            ('\n', TokenBuilderContent::LineComment)
+            | ('/', TokenBuilderContent::BlockComment(true)) =>
+                self.content = TokenBuilderContent::None,
+            (_, TokenBuilderContent::BlockComment(star)) => *star = ch == '*',
+            ('/' | '*', TokenBuilderContent::Operator(op)) if op.last() == Some('/') => {
+                op.remove_last();
+                self.flush_operators(tokens)?;
+                self.span.start = location.with_col(location.col().saturating_sub(1));
+                self.content = if ch == '/' {
+                    TokenBuilderContent::LineComment
+                } else {
+                    TokenBuilderContent::BlockComment(false)
+                };
+            }
+
+            // Number starting with a dot, like `.5`
+            ('0'..='9', TokenBuilderContent::Operator(op)) if op.last() == Some('.') => {
+                op.remove_last();
+                self.flush_operators(tokens)?;
+                self.span.start = location.with_col(location.col().saturating_sub(1));
+                self.span.end = location;
+                self.content = TokenBuilderContent::Number(format!(".{ch}"));
+            }
 
             // Parse operator
             (
                 '(' | ')' | '[' | ']' | '{' | '}' | '~' | '!' | '*' | '&' | '%' | '/' | '>' | '<'
-                | '=' | '|' | '^' | ',' | '?' | ':' | ';' | '.' | '+' | '-',
+                | '=' | '|' | '^' | ',' | '?' | ':' | ';' | '.' | '+' | '-' | '#',
                 TokenBuilderContent::Operator(op),
             ) =>
-                if let Some(operator) = op.push(ch) {
-                    self.push_token(tokens);
-                    // tokens.push(LToken {
-                    //     span: self.span,
-                    //     token: Token::Operator(operator),
-                    // });
+                if let Some((operator, len)) = op.push(ch).map_err(|_ch| self.invalid_operator())? {
+                    self.push_operator(tokens, operator, len);
                 },
+            // End of identifier or number: the character belongs to the next token
             (
                 _,
                 TokenBuilderContent::Number(_)
                 | TokenBuilderContent::Ident(..)
-                | TokenBuilderContent::Operator(_),
-            ) => return Err("invalid character".into()),
-            (_, TokenBuilderContent::None) => todo!(),
+                | TokenBuilderContent::Directive(_),
+            ) => {
+                self.push_token(tokens)?;
+                return self.lex_char(tokens, ch, location);
+            }
+            // End of operators: the character belongs to the next token
+            (_, TokenBuilderContent::Operator(_)) => {
+                self.flush_operators(tokens)?;
+                return self.lex_char(tokens, ch, location);
+            }
+
+            // Skip comments and whitespaces
+            (_, TokenBuilderContent::LineComment)
+            | (' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C', TokenBuilderContent::None) => (),
+            // Start a new token
+            (_, TokenBuilderContent::None) => {
+                self.content = match ch {
+                    'a'..='z' | 'A'..='Z' | '_' => TokenBuilderContent::Ident(ch.into()),
+                    '0'..='9' => TokenBuilderContent::Number(ch.into()),
+                    '"' | '\'' => TokenBuilderContent::quoted(ch),
+                    '#' if !self.line_started => TokenBuilderContent::Directive(String::new()),
+                    '(' | ')' | '[' | ']' | '{' | '}' | '~' | '!' | '*' | '&' | '%' | '/' | '>'
+                    | '<' | '=' | '|' | '^' | ',' | '?' | ':' | ';' | '.' | '+' | '-' | '#' =>
+                        TokenBuilderContent::Operator(OperatorBuilder::One(ch)),
+                    _ => return Err(self.error("invalid character", location.clone(), location)),
+                };
+                self.span.start = location.clone();
+                self.span.end = location;
+            }
         }
         Ok(())
     }
+
+    /// Reads `ch`, at `location`, in the escape sequence being read, and
+    /// decodes the sequence once it is complete. Returns `false` if `ch` isn't
+    /// part of the sequence, but ends it, like the `8` of `"\18"`.
+    fn lex_escape(&mut self, ch: char, location: Location) -> Result<bool, LexError<'filepath>> {
+        let Some(mut sequence) = self.escape.take() else {
+            return Ok(false);
+        };
+        let extends = Literal::extends_escape(&sequence, ch);
+        let end = if extends {
+            sequence.push(ch);
+            if !Literal::is_complete_escape(&sequence) {
+                self.escape = Some(sequence);
+                return Ok(true);
+            }
+            location
+        } else {
+            // The sequence ends with an `x` or a digit, one column wide
+            location.with_col(location.col().saturating_sub(1))
+        };
+        let Some(decoded) = Literal::unescape(&sequence, self.encoding) else {
+            let start = self.escape_start.clone();
+            return Err(self.error(&format!("invalid escape sequence `\\{sequence}`"), start, end));
+        };
+        if let TokenBuilderContent::Char(string) | TokenBuilderContent::String(string) =
+            &mut self.content
+        {
+            string.push(decoded);
+        }
+        Ok(extends)
+    }
+
+    /// Pushes an operator of `len` characters, starting at the start of the
+    /// span, and moves the span to the next character.
+    fn push_operator(&mut self, tokens: &mut Vec<LToken<'a>>, operator: Operator, len: usize) {
+        let start = mem::take(&mut self.span.start);
+        let col = start.col().saturating_add(len);
+        let end = start.with_col(col.saturating_sub(1));
+        self.span.start = start.with_col(col);
+        let span = TokenSpan { filepath: self.span.filepath, start, end };
+        let token = Token::Operator(operator);
+        let id = self.take_id();
+        self.line_started = true;
+        tokens.push(LToken { id, preprocessor: self.preprocessor, span, token });
+    }
+
+    fn push_token(&mut self, tokens: &mut Vec<LToken<'a>>) -> Result<(), LexError<'filepath>> {
+        let filepath = self.span.filepath;
+        let encoding = mem::take(&mut self.encoding);
+        let (standard, gnu) = (self.standard, self.gnu);
+        if let Some(mut token) = self
+            .content
+            .take_token(&self.span, encoding, standard, gnu)?
+        {
+            if let Token::Directive(name) = &token {
+                self.preprocessor = true;
+                self.condition = name == "if" || name == "elif";
+            } else if self.condition && matches!(&token, Token::Symbol(name) if name == "defined") {
+                token = Token::Operator(Operator::Defined);
+            }
+            let span = mem::take(&mut self.span);
+            let id = self.take_id();
+            self.line_started = true;
+            tokens.push(LToken { id, preprocessor: self.preprocessor, span, token });
+            self.span.filepath = filepath;
+        }
+        Ok(())
+    }
+
+    /// Drops the token being built after `error`, and pushes an error token
+    /// instead.
+    fn recover(&mut self, tokens: &mut Vec<LToken<'a>>, error: &LexError<'filepath>) {
+        self.content = TokenBuilderContent::None;
+        self.encoding = Encoding::Plain;
+        self.escape = None;
+        let span = error.span.clone();
+        let id = self.take_id();
+        tokens.push(LToken { id, preprocessor: self.preprocessor, span, token: Token::Error });
+    }
+
+    /// Returns the id of the next token, and moves to the following one.
+    const fn take_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id = id.saturating_add(1);
+        id
+    }
+
+    /// Returns the error for the char or string literal being built, ended by
+    /// the end of the line or of the input. It points at the opening quote.
+    fn unterminated(&self) -> LexError<'filepath> {
+        let kind = if matches!(self.content, TokenBuilderContent::Char(_)) {
+            "char"
+        } else {
+            "string"
+        };
+        let start = self.span.start.clone();
+        self.error(&format!("unterminated {kind} literal"), start.clone(), start)
+    }
+}
+
+/// Settings of the lexer, see [`lex_chars`].
+struct LexConfig {
+    /// Maximum number of characters to lex, line ends excluded.
+    budget: usize,
+    /// The GNU keywords are recognised, see [`Keyword::from_gnu_value`].
+    gnu: bool,
+    /// Lexing goes on after an error.
+    recover: bool,
+    /// Standard whose keywords are recognised.
+    standard: Standard,
+    /// Unit of the columns.
+    unit: ColumnUnit,
+}
+
+impl Default for LexConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            budget: usize::MAX,
+            gnu: false,
+            recover: false,
+            standard: Standard::C23,
+            unit: ColumnUnit::Chars,
+        }
+    }
 }
 
 /// lexicalize the provided lines. It is the responsability of the user to
 /// ensure that lines belong to filepath.
+///
+/// Columns count Unicode code points, see [`lex_with_columns`] for other
+/// units, and keywords are the ones of C23, see [`lex_with_standard`] for
+/// other standards.
+///
+/// # Errors
+///
+/// Returns the first error of the source, like an invalid character or an
+/// unterminated literal, see [`lex_recovering`] to get all of them.
+#[inline]
 pub fn lex<'b, 'filepath: 'b>(
     filepath: &'filepath Path,
     lines: Lines<'_>,
-) -> Result<Tokens<'b>, String> {
-    let mut tokens = vec![];
-    let mut builder = TokenBuilder::default();
-    let mut location = Location::from(filepath);
-    for line in lines {
-        for ch in line.chars() {
-            builder.lex_char(&mut tokens, ch, location.clone())?;
-            location.incr_col();
+) -> Result<Tokens<'b>, LexError<'b>> {
+    let source: String = line_chars(lines).collect();
+    lex_str(filepath, &source)
+}
+
+/// Lexes `source`, the content of the file at `filepath`.
+///
+/// Unlike [`lex`], the source is split into lines by the lexer, so `\n`,
+/// `\r\n` and `\r` line ends are all tracked, including in the byte offsets
+/// of the locations.
+///
+/// # Errors
+///
+/// Returns an error if the source is invalid.
+#[inline]
+pub fn lex_str<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    source: &str,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    lex_until_error(filepath, source.chars(), &LexConfig::default())
+}
+
+/// Reads the file at `path` and lexes it.
+///
+/// The returned tokens own their path, so they don't borrow `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, or if its content is invalid.
+#[inline]
+pub fn lex_file(path: &Path) -> Result<Vec<OwnedLToken>, LexError<'_>> {
+    let source = fs::read_to_string(path).map_err(|error| {
+        let span = TokenSpan { filepath: Some(path), ..Default::default() };
+        LexError::new(format!("cannot read file: {error}"), span).with_kind(LexErrorKind::Io)
+    })?;
+    let tokens = lex_str(path, &source)?;
+    Ok(tokens.into_iter().map(OwnedLToken::from).collect())
+}
+
+/// Same as [`lex`], but fails once more than `budget` characters were lexed.
+///
+/// This guards against huge inputs when lexing untrusted sources. Line ends
+/// are not counted.
+///
+/// # Errors
+///
+/// Returns an error if the budget is exceeded, or if the source is invalid.
+#[inline]
+pub fn lex_with_budget<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+    budget: usize,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    lex_until_error(filepath, line_chars(lines), &LexConfig { budget, ..Default::default() })
+}
+
+/// Same as [`lex`], but counts the columns in `unit` instead of code points.
+///
+/// For instance, `é` is 2 columns wide in [`ColumnUnit::Bytes`], and `😀` is 2
+/// columns wide in [`ColumnUnit::Utf16`].
+///
+/// # Errors
+///
+/// Returns an error if the source is invalid.
+#[inline]
+pub fn lex_with_columns<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+    unit: ColumnUnit,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    lex_until_error(filepath, line_chars(lines), &LexConfig { unit, ..Default::default() })
+}
+
+/// Same as [`lex`], but recognises the keywords of `standard` and the GNU
+/// extensions, like `__attribute__` or `__inline__`, see
+/// [`Keyword::from_gnu_value`].
+///
+/// # Errors
+///
+/// Returns an error if the source is invalid.
+#[inline]
+pub fn lex_with_gnu<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+    standard: Standard,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    let config = LexConfig { gnu: true, standard, ..Default::default() };
+    lex_until_error(filepath, line_chars(lines), &config)
+}
+
+/// Same as [`lex`], but recognises the keywords of `standard` instead of C23.
+///
+/// Keywords introduced by a later standard are identifiers, like `bool` in
+/// C99, where `_Bool` is the keyword.
+///
+/// # Errors
+///
+/// Returns an error if the source is invalid.
+#[inline]
+pub fn lex_with_standard<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+    standard: Standard,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    lex_until_error(filepath, line_chars(lines), &LexConfig { standard, ..Default::default() })
+}
+
+/// Same as [`lex`], but with a symbol table seeded by the caller, e.g. with
+/// the predefined macros.
+///
+/// The lexer doesn't declare symbols, but records each use of a symbol of the
+/// file scope, [`SymbolTable::FILE_SCOPE`], see
+/// [`SymbolTableEntry::references`](symbol::SymbolTableEntry::references).
+///
+/// # Errors
+///
+/// Returns an error if the source is invalid.
+#[inline]
+pub fn lex_with_symbols<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+    symbols: &mut SymbolTable,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    let tokens = lex(filepath, lines)?;
+    for token in &tokens {
+        if let Token::Symbol(name) = &token.token {
+            symbols.reference(SymbolTable::FILE_SCOPE, name, token.span.start.clone());
         }
-        location.incr_line();
     }
     Ok(tokens)
 }
+
+/// Lexes `source`, the content of the file at `filepath`, on a new thread,
+/// e.g. to parse the tokens while the rest of the file is lexed.
+///
+/// The tokens are sent line by line over the returned channel, which holds at
+/// most `bound` tokens: the lexer waits for the receiver to catch up. The
+/// thread returns the error that stopped lexing, if any.
+#[inline]
+#[must_use]
+pub fn spawn_lexer(
+    filepath: PathBuf,
+    source: String,
+    bound: usize,
+) -> (Receiver<OwnedLToken>, JoinHandle<Result<(), OwnedLexError>>) {
+    let (sender, receiver) = mpsc::sync_channel(bound);
+    let handle = thread::spawn(move || {
+        // Tokens are dropped if the receiver hung up
+        let mut send = |tokens: &mut Tokens<'_>| {
+            for token in tokens.drain(..) {
+                if sender.send(OwnedLToken::from(token)).is_err() {
+                    break;
+                }
+            }
+        };
+        let (mut tokens, mut errors) =
+            lex_chars(&filepath, source.chars(), &LexConfig::default(), &mut send);
+        send(&mut tokens);
+        errors.pop().map_or(Ok(()), |error| Err(error.into()))
+    });
+    (receiver, handle)
+}
+
+/// Same as [`lex`], but keeps lexing after an error.
+///
+/// The invalid source is replaced by a [`Token::Error`], and the characters
+/// following the error are skipped up to the next whitespace. Returns the
+/// tokens along with all the errors.
+#[inline]
+#[must_use]
+pub fn lex_recovering<'tokens, 'filepath: 'tokens>(
+    filepath: &'filepath Path,
+    lines: Lines<'_>,
+) -> (Tokens<'tokens>, Vec<LexError<'tokens>>) {
+    let config = LexConfig { recover: true, ..Default::default() };
+    lex_chars(filepath, line_chars(lines), &config, |_| ())
+}
+
+/// Checks that every token is a valid C preprocessing token, e.g. before
+/// feeding the stream to a preprocessor.
+///
+/// This is a coarse check: the stream is invalid if it contains a
+/// [`Token::Error`], left by [`lex_recovering`] on invalid characters,
+/// literals or comments.
+#[inline]
+#[must_use]
+pub fn is_valid_pp_tokens(tokens: &[LToken<'_>]) -> bool {
+    tokens.iter().all(|token| token.token != Token::Error)
+}
+
+/// Returns the first token, or [`None`] if there are only [`Token::Eof`]s.
+#[inline]
+#[must_use]
+pub fn first_token<'tokens, 'filepath>(
+    tokens: &'tokens [LToken<'filepath>],
+) -> Option<&'tokens LToken<'filepath>> {
+    tokens.iter().find(|token| token.token != Token::Eof)
+}
+
+/// Returns the last token, ignoring the trailing [`Token::Eof`].
+#[inline]
+#[must_use]
+pub fn last_token<'tokens, 'filepath>(
+    tokens: &'tokens [LToken<'filepath>],
+) -> Option<&'tokens LToken<'filepath>> {
+    tokens.iter().rev().find(|token| token.token != Token::Eof)
+}
+
+/// Returns the span from the start of the first token to the end of the last
+/// one, e.g. to report an error somewhere in a file or a region.
+///
+/// Returns [`None`] if there are no tokens but [`Token::Eof`]s.
+#[inline]
+#[must_use]
+pub fn tokens_span<'filepath>(tokens: &[LToken<'filepath>]) -> Option<TokenSpan<'filepath>> {
+    let first = first_token(tokens)?;
+    let last = last_token(tokens)?;
+    Some(first.span.merge(&last.span))
+}
+
+/// Groups the errors of lexing several files by file, e.g. the results of
+/// [`lex`] or [`lex_file`] on each file.
+///
+/// The successful results are skipped, and the errors without a file are
+/// under an empty path.
+#[inline]
+#[must_use]
+pub fn diagnostics_by_file<'filepath, T, I>(
+    results: I,
+) -> HashMap<PathBuf, Vec<LexError<'filepath>>>
+where
+    I: IntoIterator<Item = Result<T, LexError<'filepath>>>,
+{
+    let mut diagnostics = HashMap::<_, Vec<_>>::new();
+    for error in results.into_iter().filter_map(Result::err) {
+        let filepath = error
+            .span
+            .filepath
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        diagnostics.entry(filepath).or_default().push(error);
+    }
+    diagnostics
+}
+
+/// Returns `source` with the kind of each token appended to it, like
+/// `int[KEYWORD] x[SYMBOL]`.
+///
+/// `source` must be the content of the file the tokens were lexed from, and
+/// the tokens must be in source order.
+#[inline]
+#[must_use]
+pub fn annotate(source: &str, tokens: &[LToken<'_>]) -> String {
+    let mut annotated = String::with_capacity(source.len());
+    let mut copied: usize = 0;
+    for token in tokens {
+        if token.token == Token::Eof {
+            continue;
+        }
+        // The span ends at the start of the last character of the token
+        let last = token.span.end.offset();
+        let Some(ch) = source.get(last..).and_then(|rest| rest.chars().next()) else {
+            continue;
+        };
+        let end = last.saturating_add(ch.len_utf8());
+        if let Some(text) = source.get(copied..end) {
+            annotated.push_str(text);
+            copied = end;
+        }
+        annotated.push('[');
+        annotated.push_str(token.token.kind());
+        annotated.push(']');
+    }
+    annotated.push_str(source.get(copied..).unwrap_or_default());
+    annotated
+}
+
+/// Returns the number of spaces and tabs right before the token, on its line.
+///
+/// `source` must be the content of the file the token was lexed from.
+#[inline]
+#[must_use]
+pub fn leading_spaces(source: &str, token: &LToken<'_>) -> usize {
+    source_line(source, token).map_or(0, |line| {
+        let before: Vec<char> = line.chars().take(token.span.start.col()).collect();
+        before
+            .iter()
+            .rev()
+            .take_while(|ch| matches!(ch, ' ' | '\t'))
+            .count()
+    })
+}
+
+/// Returns the full line on which the token starts.
+///
+/// `source` must be the content of the file the token was lexed from. For
+/// tokens spanning multiple lines, the first one is returned.
+#[inline]
+#[must_use]
+pub fn source_line<'source>(source: &'source str, token: &LToken<'_>) -> Option<&'source str> {
+    split_lines(source)
+        .nth(token.span.start.line())
+        .map(strip_line_end)
+}
+
+/// Splits the token stream before the first token starting at or after
+/// `location`.
+///
+/// `tokens` must be sorted, as returned by [`lex`].
+#[inline]
+#[must_use]
+pub fn split_at_location<'tokens, 'filepath>(
+    tokens: &'tokens [LToken<'filepath>],
+    location: &Location,
+) -> (&'tokens [LToken<'filepath>], &'tokens [LToken<'filepath>]) {
+    tokens.split_at(tokens.partition_point(|token| token.span.start < *location))
+}
+
+/// Returns the token at `location` with up to `radius` tokens on each side.
+///
+/// The token at `location` is the first one that ends at or after it, so a
+/// location between two tokens refers to the next one. The returned slice is
+/// empty if no token ends at or after `location`. `tokens` must be sorted, as
+/// returned by [`lex`].
+#[inline]
+#[must_use]
+pub fn context_around<'tokens, 'filepath>(
+    tokens: &'tokens [LToken<'filepath>],
+    location: &Location,
+    radius: usize,
+) -> &'tokens [LToken<'filepath>] {
+    let index = tokens.partition_point(|token| token.span.end < *location);
+    if index == tokens.len() {
+        return &[];
+    }
+    let end = index
+        .saturating_add(radius)
+        .saturating_add(1)
+        .min(tokens.len());
+    tokens
+        .get(index.saturating_sub(radius)..end)
+        .unwrap_or_default()
+}
+
+/// Writes a human-readable dump of the tokens to `writer`, one token per line
+/// prefixed with its human location.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+#[inline]
+pub fn write_tokens<W: Write>(writer: &mut W, tokens: &[LToken<'_>]) -> io::Result<()> {
+    for token in tokens {
+        writeln!(writer, "{token}")?;
+    }
+    Ok(())
+}
+
+/// Writes the tokens to `writer` as JSON lines: one JSON object per token and
+/// per line, like
+/// `{"line":1,"col":5,"end_line":1,"end_col":7,"kind":"SYMBOL","text":"foo"}`.
+///
+/// The locations are human ones, starting at 1, and `text` is the spelling of
+/// the token.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+#[inline]
+pub fn write_tokens_jsonl<W: Write>(writer: &mut W, tokens: &[LToken<'_>]) -> io::Result<()> {
+    for token in tokens {
+        let (line, col) = token.span.start.human();
+        let (end_line, end_col) = token.span.end.human();
+        let kind = token.token.kind();
+        write!(writer, r#"{{"line":{line},"col":{col},"end_line":{end_line},"#)?;
+        write!(writer, r#""end_col":{end_col},"kind":"{kind}","text":""#)?;
+        for ch in token.token.to_string().chars() {
+            match ch {
+                '"' => writer.write_all(br#"\""#)?,
+                '\\' => writer.write_all(br"\\")?,
+                '\n' => writer.write_all(br"\n")?,
+                '\t' => writer.write_all(br"\t")?,
+                _ if ch.is_control() => write!(writer, r"\u{:04x}", u32::from(ch))?,
+                _ => write!(writer, "{ch}")?,
+            }
+        }
+        writer.write_all(b"\"}\n")?;
+    }
+    Ok(())
+}
+
+/// Returns a human-readable dump of the tokens, in the format of
+/// [`write_tokens`].
+#[inline]
+#[must_use]
+pub fn dump_tokens(tokens: &[LToken<'_>]) -> String {
+    let mut dump = vec![];
+    // Writing to a vector can't fail, and the tokens are written as UTF-8
+    write_tokens(&mut dump, tokens)
+        .ok()
+        .and_then(|()| String::from_utf8(dump).ok())
+        .unwrap_or_default()
+}
+
+/// Counts the occurrences of each token, identified by its spelling.
+#[inline]
+#[must_use]
+pub fn token_histogram(tokens: &[LToken<'_>]) -> BTreeMap<String, usize> {
+    let mut histogram = BTreeMap::<_, usize>::new();
+    for token in tokens {
+        let count = histogram.entry(token.token.to_string()).or_default();
+        *count = count.saturating_add(1);
+    }
+    histogram
+}
+
+/// Moves the spans of the tokens at or after `after`, to follow an edit of the
+/// source at that location.
+///
+/// The lines are moved by `line_delta`, the columns by `col_delta` only on
+/// the line of the edit, and the byte offsets by `byte_delta`, the number of
+/// bytes inserted, or removed if negative.
+#[inline]
+pub fn shift_spans(
+    tokens: &mut [LToken<'_>],
+    after: &Location,
+    line_delta: isize,
+    col_delta: isize,
+    byte_delta: isize,
+) {
+    for token in tokens {
+        for location in [&mut token.span.start, &mut token.span.end] {
+            if location.is_before(after) {
+                continue;
+            }
+            let col = if location.line() == after.line() {
+                location.col().saturating_add_signed(col_delta)
+            } else {
+                location.col()
+            };
+            let line = location.line().saturating_add_signed(line_delta);
+            *location = location.with_position(line, col, byte_delta);
+        }
+    }
+}
+
+/// Lexes `token` again after a small edit of `source` inside it, like
+/// changing a digit of a number.
+///
+/// `source` must be the edited content of the file the token was lexed from,
+/// with the token still at the same location. The id and the span of the
+/// token are kept.
+///
+/// # Errors
+///
+/// Returns an error if the edited text is invalid, or if it no longer forms a
+/// single token with the same span: the edit moved the token boundaries, so a
+/// wider part of the source must be lexed again.
+#[inline]
+pub fn relex_token<'filepath>(
+    source: &str,
+    token: &LToken<'filepath>,
+) -> Result<LToken<'filepath>, LexError<'filepath>> {
+    let start = &token.span.start;
+    let boundary_error =
+        || LexError::new("the edit changed the boundaries of the token", token.span.clone());
+    let line = source_line(source, token).ok_or_else(boundary_error)?;
+    let rest = line
+        .char_indices()
+        .nth(start.col())
+        .and_then(|(index, _)| line.get(index..))
+        .ok_or_else(boundary_error)?;
+    // The rest of the line is lexed, to detect a token growing past its end.
+    // Only the first token matters, so the lexer recovers from the errors
+    // after it, like a block comment that ends on a later line.
+    let filepath = token.span.filepath.unwrap_or_else(|| Path::new(""));
+    let config = LexConfig { recover: true, ..Default::default() };
+    let (relexed, errors) = lex_chars(filepath, rest.chars(), &config, |_| ());
+    let first = relexed.into_iter().next().ok_or_else(boundary_error)?;
+    if first.token == Token::Error {
+        let error = errors.into_iter().next().ok_or_else(boundary_error)?;
+        let col = |location: &Location| start.col().saturating_add(location.col());
+        let span = TokenSpan {
+            filepath: token.span.filepath,
+            start: start.with_col(col(&error.span.start)),
+            end: start.with_col(col(&error.span.end)),
+        };
+        return Err(LexError { span, ..error });
+    }
+    let end = start.col().saturating_add(first.span.end.col());
+    if first.token == Token::Eof || end != token.span.end.col() || first.span.end.line() != 0 {
+        return Err(boundary_error());
+    }
+    Ok(LToken {
+        id: token.id,
+        preprocessor: token.preprocessor,
+        span: token.span.clone(),
+        token: first.token,
+    })
+}
+
+/// Returns the text and the span of every string literal, in order.
+#[inline]
+#[must_use]
+pub fn string_literals<'tokens, 'filepath>(
+    tokens: &'tokens [LToken<'filepath>],
+) -> Vec<(&'tokens str, &'tokens TokenSpan<'filepath>)> {
+    tokens
+        .iter()
+        .filter_map(|token| match &token.token {
+            Token::Literal(Literal::String(string, _)) => Some((string.as_str(), &token.span)),
+            Token::Literal(_)
+            | Token::Keyword(_)
+            | Token::Operator(_)
+            | Token::Symbol(_)
+            | Token::Directive(_)
+            | Token::Eof
+            | Token::Error => None,
+        })
+        .collect()
+}
+
+/// Concatenates the adjacent string literals, like `"a" "b"`, into one token
+/// spanning them, as after preprocessing.
+///
+/// The strings of a directive are left alone. The result takes the encoding
+/// of the prefixed strings, so `L"a" "b"` is `L"ab"`, but strings of different
+/// prefixes, like `u"a" U"b"`, can't be concatenated.
+///
+/// # Errors
+///
+/// Returns an error spanning both strings if their prefixes differ.
+#[inline]
+pub fn concat_adjacent_strings<'filepath>(
+    tokens: Tokens<'filepath>,
+) -> Result<Tokens<'filepath>, LexError<'filepath>> {
+    let mut concatenated: Tokens<'filepath> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        match (concatenated.last_mut(), &token.token) {
+            (
+                Some(LToken {
+                    preprocessor: false,
+                    span,
+                    token: Token::Literal(Literal::String(string, encoding)),
+                    ..
+                }),
+                Token::Literal(Literal::String(next, next_encoding)),
+            ) if !token.preprocessor => {
+                let merged = span.merge(&token.span);
+                *encoding = match (*encoding, *next_encoding) {
+                    (Encoding::Plain, prefixed) | (prefixed, Encoding::Plain) => prefixed,
+                    (first, second) if first == second => first,
+                    (first, second) => {
+                        let message = format!(
+                            "cannot concatenate string literals prefixed with `{first}` and \
+                             `{second}`"
+                        );
+                        return Err(LexError::new(message, merged));
+                    }
+                };
+                string.push_str(next);
+                *span = merged;
+            }
+            _ => concatenated.push(token),
+        }
+    }
+    Ok(concatenated)
+}
+
+/// Lexes the characters with the settings of `config`.
+///
+/// `\n`, `\r\n` and `\r` each end a line. When [`LexConfig::recover`] is set,
+/// lexing goes on after an error, see [`lex_recovering`]; otherwise, the first
+/// error is the last element returned.
+///
+/// After each line, `sink` is given the tokens lexed so far, which it may
+/// take. The remaining tokens are returned.
+fn lex_chars<'tokens, 'filepath: 'tokens, I, F>(
+    filepath: &'filepath Path,
+    source: I,
+    config: &LexConfig,
+    mut sink: F,
+) -> (Tokens<'tokens>, Vec<LexError<'tokens>>)
+where
+    I: Iterator<Item = char>,
+    F: FnMut(&mut Tokens<'tokens>),
+{
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    let mut steps: usize = 0;
+    let mut builder = TokenBuilder {
+        span: TokenSpan { filepath: Some(filepath), ..Default::default() },
+        gnu: config.gnu,
+        standard: config.standard,
+        ..Default::default()
+    };
+    let mut location = Location::default();
+    // Location just past the last character
+    let mut end = Location::default();
+    // Set after an error, until the next whitespace
+    let mut skipping = false;
+    let mut chars = source.peekable();
+    while let Some(ch) = chars.next() {
+        // Line splice: a backslash right before a line end joins the two lines
+        // in every state, so a directive or a token goes on on the next line
+        if ch == '\\' && chars.peek().is_some_and(|next| LINE_ENDS.contains(next)) {
+            location.advance_in(ch, config.unit);
+            if chars.next() == Some('\r') && chars.next_if_eq(&'\n').is_some() {
+                location.advance('\r');
+            }
+            location.incr_line();
+            continue;
+        }
+        let is_line_end = LINE_ENDS.contains(&ch);
+        let result = if is_line_end {
+            end = location.clone();
+            skipping = false;
+            // The line end terminates the current token, whatever its style
+            let result = builder.lex_char(&mut tokens, '\n', location.clone());
+            if ch == '\r' && chars.next_if_eq(&'\n').is_some() {
+                location.advance('\r');
+            }
+            builder.condition = false;
+            builder.line_started = false;
+            builder.preprocessor = false;
+            location.incr_line();
+            result
+        } else if skipping && !ch.is_whitespace() {
+            location.advance_in(ch, config.unit);
+            continue;
+        } else {
+            skipping = false;
+            steps = steps.saturating_add(1);
+            if steps > config.budget {
+                let error = builder.error("lexing budget exceeded", location.clone(), location);
+                errors.push(error.with_kind(LexErrorKind::BudgetExceeded));
+                return (tokens, errors);
+            }
+            let result = builder.lex_char(&mut tokens, ch, location.clone());
+            location.advance_in(ch, config.unit);
+            end = location.clone();
+            result
+        };
+        if let Err(error) = result {
+            if !config.recover {
+                errors.push(error);
+                return (tokens, errors);
+            }
+            builder.recover(&mut tokens, &error);
+            errors.push(error);
+            skipping = !is_line_end;
+        }
+        if is_line_end {
+            sink(&mut tokens);
+        }
+    }
+    if let Err(error) = builder.finish(&mut tokens) {
+        if !config.recover {
+            errors.push(error);
+            return (tokens, errors);
+        }
+        builder.recover(&mut tokens, &error);
+        errors.push(error);
+    }
+    let span = TokenSpan { filepath: Some(filepath), start: end.clone(), end };
+    tokens.push(LToken { id: builder.take_id(), preprocessor: false, span, token: Token::Eof });
+    (tokens, errors)
+}
+
+/// Lexes the characters up to the first error, with the settings of `config`,
+/// which must not set [`LexConfig::recover`].
+fn lex_until_error<'tokens, 'filepath: 'tokens, I: Iterator<Item = char>>(
+    filepath: &'filepath Path,
+    source: I,
+    config: &LexConfig,
+) -> Result<Tokens<'tokens>, LexError<'tokens>> {
+    let (tokens, mut errors) = lex_chars(filepath, source, config, |_| ());
+    errors.pop().map_or(Ok(tokens), Err)
+}
+
+/// Returns the characters of the lines, each line being ended by a `\n`.
+///
+/// [`Lines`] already drops the `\r` of `\r\n`, but keeps the lone `\r`, which
+/// the lexer then treats as a line end.
+fn line_chars(lines: Lines<'_>) -> impl Iterator<Item = char> + '_ {
+    lines.flat_map(|line| line.chars().chain(['\n']))
+}
+
+/// Splits `source` into lines, each with its line end, among `\n`, `\r\n` and
+/// a lone `\r`, as the lexer does.
+pub(crate) fn split_lines(source: &str) -> impl Iterator<Item = &str> {
+    let mut rest = source;
+    iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest.find(LINE_ENDS).map_or(rest.len(), |index| {
+            let crlf = rest.get(index..).is_some_and(|end| end.starts_with("\r\n"));
+            index.saturating_add(if crlf { 2 } else { 1 })
+        });
+        let (line, tail) = rest.split_at(end);
+        rest = tail;
+        Some(line)
+    })
+}
+
+/// Removes the line end of `line`, as returned by [`split_lines`].
+pub(crate) fn strip_line_end(line: &str) -> &str {
+    line.trim_end_matches(LINE_ENDS)
+}
+
+/// Returns the byte offset of the `col`-th character of the `line`-th line of
+/// `source`.
+///
+/// The column may be the one just past the last character of the line.
+fn byte_offset(source: &str, line: usize, col: usize) -> Option<usize> {
+    let mut offset: usize = 0;
+    for text in split_lines(source).take(line) {
+        offset = offset.saturating_add(text.len());
+    }
+    let text = split_lines(source.get(offset..)?)
+        .next()
+        .unwrap_or_default();
+    let in_line = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([text.len()])
+        .nth(col)?;
+    Some(offset.saturating_add(in_line))
+}
@@ -1,4 +1,497 @@
+//! Module to define the [`Literal`] type.
+
+use core::{fmt, iter};
+
+/// Literal value written in the source.
+#[derive(Debug, PartialEq)]
+#[expect(
+    clippy::exhaustive_enums,
+    reason = "consumers must handle every literal"
+)]
 pub enum Literal {
-    Char(char),
-    Number(String)
-}
\ No newline at end of file
+    /// Char literal, e.g. `'a'`, with its encoding.
+    Char(char, Encoding),
+    /// Floating-point number literal, e.g. `1.5e3`, with its suffix.
+    Float(f64, FloatSuffix),
+    /// Integer literal, in any base, e.g. `0x1F`, with its suffix.
+    Int(u64, IntSuffix),
+    /// String literal, without the quotes, with its encoding.
+    String(String, Encoding),
+}
+
+impl Literal {
+    /// Returns the value of the literal as an integer, if it has one.
+    ///
+    /// In C, a char literal is an `int` whose value is the code point of the
+    /// character, so `'A'` is 65.
+    #[inline]
+    #[must_use]
+    pub fn as_int_value(&self) -> Option<u64> {
+        match *self {
+            Self::Char(ch, _) => Some(u64::from(u32::from(ch))),
+            Self::Int(int, _) => Some(int),
+            Self::Float(..) | Self::String(..) => None,
+        }
+    }
+
+    /// Checks if `ch` continues the escape sequence made of `sequence`, the
+    /// characters after the backslash so far.
+    ///
+    /// Any character starts a sequence, up to 3 octal digits make an octal
+    /// sequence, as in `"\012"`, and any number of hexadecimal digits follow an
+    /// `x`, as in `"\x41"`. A universal character name is made of 4
+    /// hexadecimal digits after a `u`, as in `"\u00E9"`, or of 8 after a `U`.
+    pub(crate) fn extends_escape(sequence: &str, ch: char) -> bool {
+        match sequence.chars().next() {
+            None => true,
+            Some('x') => ch.is_ascii_hexdigit(),
+            Some('u') => sequence.len() < 5 && ch.is_ascii_hexdigit(),
+            Some('U') => sequence.len() < 9 && ch.is_ascii_hexdigit(),
+            Some('0'..='7') => sequence.len() < 3 && matches!(ch, '0'..='7'),
+            Some(_) => false,
+        }
+    }
+
+    /// Makes the char literal made of `chars`, the characters between the
+    /// quotes.
+    ///
+    /// Several characters make a multi-character constant, like `'AB'`: an
+    /// `int` whose value is given by [`Literal::multichar_value`]. Returns
+    /// [`None`] if there are no characters, or if several characters are not
+    /// plain ASCII ones.
+    pub(crate) fn from_chars(chars: &str, encoding: Encoding) -> Option<Self> {
+        let mut iter = chars.chars();
+        match (iter.next(), iter.next()) {
+            (None, _) => None,
+            (Some(ch), None) => Some(Self::Char(ch, encoding)),
+            (Some(_), Some(_)) => {
+                if encoding != Encoding::Plain {
+                    return None;
+                }
+                let value = Self::multichar_value(chars)?;
+                Some(Self::Int(u64::from(value), IntSuffix::Int))
+            }
+        }
+    }
+
+    /// Parses the `digits` of the integer literal `number` in the given
+    /// `radix`, followed by an optional suffix.
+    fn from_digits(number: &str, digits_and_suffix: &str, radix: u32) -> Result<Self, String> {
+        let (digits, suffix) = IntSuffix::split(number, digits_and_suffix)?;
+        if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+            return Err(format!("invalid integer literal `{number}`"));
+        }
+        u64::from_str_radix(digits, radix)
+            .map(|int| Self::Int(int, suffix))
+            .map_err(|_err| format!("integer literal `{number}` is too large"))
+    }
+
+    /// Parses the `digits` of the hexadecimal floating-point literal `number`,
+    /// like `1.8p3` for `0x1.8p3`.
+    ///
+    /// The binary exponent is mandatory, and gives the power of 2 by which the
+    /// mantissa is multiplied: `0x1.8p3` is `1.5 * 2^3`.
+    #[expect(clippy::float_arithmetic, reason = "computes the value of the literal")]
+    fn from_hex_float(number: &str, digits: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid hexadecimal floating-point literal `{number}`");
+        let (mantissa, exponent_and_suffix) = digits.split_once(['p', 'P']).ok_or_else(|| {
+            format!("hexadecimal floating-point literal `{number}` has no exponent")
+        })?;
+        let (exponent_digits, suffix) = FloatSuffix::split(exponent_and_suffix);
+        let exponent: i32 = exponent_digits.parse().map_err(|_err| invalid())?;
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        let hex_digit = |ch: char| ch.to_digit(16).map(f64::from).ok_or_else(invalid);
+        let radix: f64 = 16.0;
+        let mut value: f64 = 0.0;
+        for ch in integer.chars() {
+            value = value.mul_add(radix, hex_digit(ch)?);
+        }
+        let mut scale: f64 = 1.0;
+        for ch in fraction.chars() {
+            scale /= radix;
+            value = scale.mul_add(hex_digit(ch)?, value);
+        }
+        let two: f64 = 2.0;
+        Ok(Self::Float(value * two.powi(exponent), suffix))
+    }
+
+    /// Parses a number literal, as written in the source.
+    ///
+    /// The number is a float if it contains a `.` or an exponent, which is a
+    /// `p` for hexadecimal floats like `0x1.8p3`, followed by an optional
+    /// suffix, see [`FloatSuffix`]. Otherwise, it is an integer,
+    /// which can be hexadecimal (`0x`), binary (`0b`), octal (leading `0`)
+    /// or decimal, followed by a suffix like `u` or `LL`, see
+    /// [`IntSuffix`].
+    ///
+    /// The digits can be separated by `'`, like `1'000`.
+    pub(crate) fn from_number(number: &str) -> Result<Self, String> {
+        if number.contains('\'') {
+            return Self::from_number(&Self::without_separators(number)?);
+        }
+        if let Some(digits) = number
+            .strip_prefix("0x")
+            .or_else(|| number.strip_prefix("0X"))
+        {
+            if digits.contains(['.', 'p', 'P']) {
+                return Self::from_hex_float(number, digits);
+            }
+            return Self::from_digits(number, digits, 16);
+        }
+        if let Some(digits) = number
+            .strip_prefix("0b")
+            .or_else(|| number.strip_prefix("0B"))
+        {
+            return Self::from_digits(number, digits, 2);
+        }
+        if number.contains(['.', 'e', 'E']) {
+            let (mantissa, suffix) = FloatSuffix::split(number);
+            return mantissa
+                .parse()
+                .map(|float| Self::Float(float, suffix))
+                .map_err(|_err| format!("invalid floating-point literal `{number}`"));
+        }
+        match number.strip_prefix('0') {
+            Some(digits) if digits.starts_with(|ch: char| ch.is_ascii_digit()) =>
+                Self::from_digits(number, digits, 8),
+            _ => Self::from_digits(number, number, 10),
+        }
+    }
+
+    /// Checks if no character can continue the escape sequence `sequence`, see
+    /// [`Literal::extends_escape`].
+    pub(crate) fn is_complete_escape(sequence: &str) -> bool {
+        match sequence.chars().next() {
+            None | Some('x') => false,
+            Some('0'..='7') => sequence.len() == 3,
+            Some('u') => sequence.len() == 5,
+            Some('U') => sequence.len() == 9,
+            Some(_) => true,
+        }
+    }
+
+    /// Returns the value of the multi-character constant made of `chars`, like
+    /// `'AB'`.
+    ///
+    /// The value is implementation-defined, this follows GCC: each character is
+    /// a byte, the last one being the least significant, and only the last four
+    /// characters are kept. Hence `'AB'` is `0x4142`. Returns [`None`] if a
+    /// character is not ASCII.
+    #[inline]
+    #[must_use]
+    pub fn multichar_value(chars: &str) -> Option<u32> {
+        chars.chars().try_fold(0, |value: u32, ch| {
+            let byte = u8::try_from(ch).ok().filter(u8::is_ascii)?;
+            Some(value.wrapping_shl(8) | u32::from(byte))
+        })
+    }
+
+    /// Returns the character represented by the simple escape sequence `\ch`,
+    /// as in `'\n'`.
+    pub(crate) const fn simple_escape(ch: char) -> Option<char> {
+        Some(match ch {
+            '"' => '"',
+            '\'' => '\'',
+            '?' => '?',
+            '\\' => '\\',
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            _ => return None,
+        })
+    }
+
+    /// Returns the character represented by the escape sequence `sequence`, the
+    /// characters after the backslash, in a literal of the given `encoding`,
+    /// or [`None`] if it is invalid.
+    ///
+    /// The value of an octal or hexadecimal sequence must fit in a code unit
+    /// of the encoding, see [`Encoding::max_code_unit`], so `"\777"` is
+    /// invalid but `L"\777"` isn't.
+    pub(crate) fn unescape(sequence: &str, encoding: Encoding) -> Option<char> {
+        let mut chars = sequence.chars();
+        let value = match chars.next()? {
+            'x' => u32::from_str_radix(chars.as_str(), 16).ok()?,
+            '0'..='7' => u32::from_str_radix(sequence, 8).ok()?,
+            'u' | 'U' if Self::is_complete_escape(sequence) =>
+                return char::from_u32(u32::from_str_radix(chars.as_str(), 16).ok()?),
+            ch if chars.as_str().is_empty() => return Self::simple_escape(ch),
+            _ => return None,
+        };
+        if value > encoding.max_code_unit() {
+            return None;
+        }
+        char::from_u32(value)
+    }
+
+    /// Removes the digit separators of `number`, like `1'000`.
+    ///
+    /// Each separator must be between two digits, which are hexadecimal digits
+    /// for hexadecimal literals.
+    fn without_separators(number: &str) -> Result<String, String> {
+        let hex = number.starts_with("0x") || number.starts_with("0X");
+        let radix = if hex { 16 } else { 10 };
+        let is_digit = |option: Option<char>| option.is_some_and(|ch| ch.is_digit(radix));
+        let mut previous = None;
+        let mut chars = number.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch == '\'' && !(is_digit(previous) && is_digit(chars.peek().copied())) {
+                return Err(format!("invalid digit separator in number literal `{number}`"));
+            }
+            previous = Some(ch);
+        }
+        Ok(number.replace('\'', ""))
+    }
+
+    /// Writes the characters of a char or string literal delimited by
+    /// `quote`, escaping the quote, the backslash and the non-printable
+    /// characters as in C, like `\n`, `\x1B` or `\u00E9`.
+    ///
+    /// An octal or hexadecimal sequence takes the digits after it, so such a
+    /// digit is written as an octal sequence, like the `1` of `"\0\061"`.
+    fn write_escaped<I: Iterator<Item = char>>(
+        formatter: &mut fmt::Formatter<'_>,
+        chars: I,
+        quote: char,
+    ) -> fmt::Result {
+        // Radix of the numeric sequence just written, if any
+        let mut radix: Option<u32> = None;
+        for ch in chars {
+            let code = u32::from(ch);
+            radix = match ch {
+                _ if radix.is_some_and(|digits| ch.is_digit(digits)) => {
+                    write!(formatter, "\\{code:03o}")?;
+                    None
+                }
+                '\0' => {
+                    formatter.write_str("\\0")?;
+                    Some(8)
+                }
+                '\x07' | '\x08' | '\x0B' | '\x0C' | '\t' | '\n' | '\r' => {
+                    let simple = match ch {
+                        '\x07' => 'a',
+                        '\x08' => 'b',
+                        '\x0B' => 'v',
+                        '\x0C' => 'f',
+                        '\t' => 't',
+                        '\n' => 'n',
+                        _ => 'r',
+                    };
+                    write!(formatter, "\\{simple}")?;
+                    None
+                }
+                '\\' => {
+                    formatter.write_str("\\\\")?;
+                    None
+                }
+                _ if ch == quote => {
+                    write!(formatter, "\\{ch}")?;
+                    None
+                }
+                ' '..='~' => {
+                    write!(formatter, "{ch}")?;
+                    None
+                }
+                '\0'..='\u{9F}' => {
+                    write!(formatter, "\\x{code:02X}")?;
+                    Some(16)
+                }
+                '\u{A0}'..='\u{FFFF}' => {
+                    write!(formatter, "\\u{code:04X}")?;
+                    None
+                }
+                _ => {
+                    write!(formatter, "\\U{code:08X}")?;
+                    None
+                }
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Encoding of a char or string literal, given by its prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "the C literal prefixes are fixed")]
+pub enum Encoding {
+    /// No prefix, e.g. `'a'`.
+    #[default]
+    Plain,
+    /// `u` prefix, e.g. `u"a"`, for `char16_t`.
+    Utf16,
+    /// `U` prefix, e.g. `U"a"`, for `char32_t`.
+    Utf32,
+    /// `u8` prefix, e.g. `u8"a"`.
+    Utf8,
+    /// `L` prefix, e.g. `L'a'`, for `wchar_t`.
+    Wide,
+}
+
+impl Encoding {
+    /// Returns the encoding given by the prefix of a literal, like `u8`.
+    #[inline]
+    #[must_use]
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "u" => Some(Self::Utf16),
+            "U" => Some(Self::Utf32),
+            "u8" => Some(Self::Utf8),
+            "L" => Some(Self::Wide),
+            _ => None,
+        }
+    }
+
+    /// Returns the largest value of a code unit of the encoding, the largest
+    /// value of an octal or hexadecimal escape sequence.
+    ///
+    /// `wchar_t` is taken as 32 bits wide, as on Linux.
+    #[inline]
+    #[must_use]
+    pub const fn max_code_unit(self) -> u32 {
+        match self {
+            Self::Plain | Self::Utf8 => 0xFF,
+            Self::Utf16 => 0xFFFF,
+            Self::Utf32 | Self::Wide => u32::MAX,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain => "",
+            Self::Utf16 => "u",
+            Self::Utf32 => "U",
+            Self::Utf8 => "u8",
+            Self::Wide => "L",
+        }
+        .fmt(f)
+    }
+}
+
+/// Suffix of a floating-point literal, giving its type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[expect(
+    clippy::exhaustive_enums,
+    reason = "the C floating-point suffixes are fixed"
+)]
+pub enum FloatSuffix {
+    /// No suffix, e.g. `1.0`.
+    #[default]
+    Double,
+    /// `f` suffix, e.g. `1.0f`.
+    Float,
+    /// `l` suffix, e.g. `1.0L`.
+    LongDouble,
+}
+
+impl FloatSuffix {
+    /// Splits the floating-point literal `number` into its mantissa and
+    /// exponent, and its suffix.
+    fn split(number: &str) -> (&str, Self) {
+        let suffix = match number.chars().next_back() {
+            Some('f' | 'F') => Self::Float,
+            Some('l' | 'L') => Self::LongDouble,
+            _ => return (number, Self::Double),
+        };
+        // The suffix is a single ASCII character
+        let len = number.len().saturating_sub(1);
+        (number.get(..len).unwrap_or_default(), suffix)
+    }
+}
+
+impl fmt::Display for FloatSuffix {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Double => "",
+            Self::Float => "f",
+            Self::LongDouble => "l",
+        }
+        .fmt(f)
+    }
+}
+
+/// Suffix of an integer literal, giving a hint on its type.
+///
+/// The suffix is made of an optional `u` and an optional `l` or `ll`, in any
+/// order and in any case, like `10u` or `3ULL`, but `lL` is invalid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "the C integer suffixes are fixed")]
+pub enum IntSuffix {
+    /// No suffix, e.g. `10`.
+    #[default]
+    Int,
+    /// `l` suffix, e.g. `10L`.
+    Long,
+    /// `ll` suffix, e.g. `10LL`.
+    LongLong,
+    /// `u` suffix, e.g. `10u`.
+    UnsignedInt,
+    /// `u` and `l` suffix, e.g. `10UL`.
+    UnsignedLong,
+    /// `u` and `ll` suffix, e.g. `10ULL`.
+    UnsignedLongLong,
+}
+
+impl IntSuffix {
+    /// Splits `digits` into the digits and the suffix of the integer literal
+    /// `number`.
+    fn split<'digits>(number: &str, digits: &'digits str) -> Result<(&'digits str, Self), String> {
+        let len = digits.trim_end_matches(['u', 'U', 'l', 'L']).len();
+        let (body, suffix) = digits.split_at_checked(len).unwrap_or((digits, ""));
+        let int_suffix = match suffix {
+            "" => Self::Int,
+            "l" | "L" => Self::Long,
+            "ll" | "LL" => Self::LongLong,
+            "u" | "U" => Self::UnsignedInt,
+            "ul" | "uL" | "Ul" | "UL" | "lu" | "lU" | "Lu" | "LU" => Self::UnsignedLong,
+            "ull" | "uLL" | "Ull" | "ULL" | "llu" | "llU" | "LLu" | "LLU" => Self::UnsignedLongLong,
+            _ => return Err(format!("invalid suffix `{suffix}` on integer literal `{number}`")),
+        };
+        Ok((body, int_suffix))
+    }
+}
+
+impl fmt::Display for IntSuffix {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int => "",
+            Self::Long => "l",
+            Self::LongLong => "ll",
+            Self::UnsignedInt => "u",
+            Self::UnsignedLong => "ul",
+            Self::UnsignedLongLong => "ull",
+        }
+        .fmt(f)
+    }
+}
+
+impl fmt::Display for Literal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(ch, encoding) => {
+                write!(f, "{encoding}'")?;
+                Self::write_escaped(f, iter::once(*ch), '\'')?;
+                f.write_str("'")
+            }
+            Self::Float(float, suffix) => write!(f, "{float:e}{suffix}"),
+            Self::Int(int, suffix) => write!(f, "{int}{suffix}"),
+            Self::String(string, encoding) => {
+                write!(f, "{encoding}\"")?;
+                Self::write_escaped(f, string.chars(), '"')?;
+                f.write_str("\"")
+            }
+        }
+    }
+}
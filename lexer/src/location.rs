@@ -1,12 +1,33 @@
+//! Module to define the [`Location`] type.
+
+use core::cmp::Ordering;
 use std::path::Path;
 
 /// Represent a location in a file
 /// The line column is 0
 /// The first column is 0
-#[derive(Clone, Default)]
+///
+/// Locations are ordered by line first, then by column. The byte offset is
+/// not compared, as it follows from the line and the column in a given file.
+#[derive(Clone, Debug, Default, Eq)]
 pub struct Location {
     line: usize,
     col: usize,
+    /// Number of bytes from the start of the file.
+    offset: usize,
+}
+
+/// Unit in which the columns of a [`Location`] are counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ColumnUnit {
+    /// UTF-8 bytes.
+    Bytes,
+    /// Unicode code points, i.e. Rust [`char`]s.
+    #[default]
+    Chars,
+    /// UTF-16 code units, as used by the Language Server Protocol.
+    Utf16,
 }
 
 pub struct FileLocation<'filepath> {
@@ -15,45 +36,158 @@ pub struct FileLocation<'filepath> {
 }
 
 impl Location {
-    pub fn new<T: Into<usize>, U: Into<usize>>(line: T, col: U) -> Self {
-        Self { line: line.into(), col: col.into() }
+    /// Moves the location past `ch`, on the same line.
+    ///
+    /// The column counts code points, see [`Location::advance_in`] for other
+    /// units.
+    #[inline]
+    pub fn advance(&mut self, ch: char) {
+        self.advance_in(ch, ColumnUnit::Chars);
+    }
+
+    /// Moves the location past `ch`, on the same line, counting the column in
+    /// `unit`.
+    #[inline]
+    pub const fn advance_in(&mut self, ch: char, unit: ColumnUnit) {
+        let width = match unit {
+            ColumnUnit::Bytes => ch.len_utf8(),
+            ColumnUnit::Chars => 1,
+            ColumnUnit::Utf16 => ch.len_utf16(),
+        };
+        self.col = self.col.saturating_add(width);
+        self.offset = self.offset.saturating_add(ch.len_utf8());
+    }
+
+    /// Returns the column of the location, starting at 0.
+    #[inline]
+    #[must_use]
+    pub const fn col(&self) -> usize {
+        self.col
     }
 
+    #[inline]
+    #[must_use]
     pub fn human(&self) -> (usize, usize) {
         (self.line.saturating_add(1), self.col.saturating_add(1))
     }
 
+    /// Moves the location past an ASCII character, see [`Location::advance`].
+    #[inline]
     pub fn incr_col(&mut self) {
-        self.col = self.col.saturating_add(1);
+        self.advance(' ');
     }
 
+    /// Moves the location to the start of the next line, past the `\n`.
+    #[inline]
     pub fn incr_line(&mut self) {
         self.line = self.line.saturating_add(1);
         self.col = 0;
+        self.offset = self.offset.saturating_add(1);
+    }
+
+    /// Checks if `self` comes strictly after `other` in the file.
+    #[inline]
+    #[must_use]
+    pub fn is_after(&self, other: &Self) -> bool {
+        self > other
+    }
+
+    /// Checks if `self` comes strictly before `other` in the file.
+    #[inline]
+    #[must_use]
+    pub fn is_before(&self, other: &Self) -> bool {
+        self < other
+    }
+
+    /// Returns the line of the location, starting at 0.
+    #[inline]
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new<T: Into<usize>, U: Into<usize>>(line: T, col: U) -> Self {
+        Self { line: line.into(), col: col.into(), offset: 0 }
+    }
+
+    /// Returns the number of bytes from the start of the file.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the location at `col` on the same line, assuming the characters
+    /// in between are ASCII.
+    pub(crate) const fn with_col(&self, col: usize) -> Self {
+        let offset = if col < self.col {
+            self.offset.saturating_sub(self.col.saturating_sub(col))
+        } else {
+            self.offset.saturating_add(col.saturating_sub(self.col))
+        };
+        Self { line: self.line, col, offset }
+    }
+
+    /// Returns the location at `line` and `col`, with the byte offset moved by
+    /// `byte_delta`.
+    pub(crate) const fn with_position(&self, line: usize, col: usize, byte_delta: isize) -> Self {
+        Self { line, col, offset: self.offset.saturating_add_signed(byte_delta) }
+    }
+}
+
+#[expect(clippy::missing_trait_methods, reason = "the defaults are consistent")]
+impl Ord for Location {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.line, self.col).cmp(&(other.line, other.col))
+    }
+}
+
+#[expect(clippy::missing_trait_methods, reason = "the defaults are consistent")]
+impl PartialEq for Location {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        (self.line, self.col) == (other.line, other.col)
+    }
+}
+
+#[expect(clippy::missing_trait_methods, reason = "the defaults are consistent")]
+impl PartialOrd for Location {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl<T: Into<usize>, U: Into<usize>> From<(T, U)> for Location {
+    #[inline]
     fn from(value: (T, U)) -> Self {
-        Self { line: value.0.into(), col: value.1.into() }
+        Self { line: value.0.into(), col: value.1.into(), offset: 0 }
     }
 }
 
 impl<'filepath> FileLocation<'filepath> {
+    #[inline]
+    #[must_use]
     pub fn new<'b: 'filepath>(filepath: &'b Path, location: Location) -> Self {
         Self { filepath: Some(filepath), location }
     }
 
+    #[inline]
     pub fn incr_col(&mut self) {
         self.location.incr_col();
     }
 
+    #[inline]
     pub fn incr_line(&mut self) {
         self.location.incr_line();
     }
 }
 
 impl<'filepath> From<&'filepath Path> for FileLocation<'filepath> {
+    #[inline]
     fn from(filepath: &'filepath Path) -> Self {
         Self { filepath: Some(filepath), location: Location::default() }
     }
@@ -0,0 +1,53 @@
+//! Module to convert spans to positions of the [Language Server Protocol](https://microsoft.github.io/language-server-protocol/specification).
+
+use crate::{TokenSpan, split_lines, strip_line_end};
+
+/// Position in a text document, as defined by the LSP.
+///
+/// Both fields start at 0, and `character` counts UTF-16 code units, not
+/// characters: an astral character like `😀` counts for 2.
+#[derive(Debug, PartialEq, Eq)]
+#[expect(clippy::exhaustive_structs, reason = "mirrors the LSP type")]
+pub struct LspPosition {
+    /// UTF-16 offset in the line.
+    pub character: u32,
+    /// Line in the document.
+    pub line: u32,
+}
+
+impl LspPosition {
+    /// Makes the position of the `chars`-th character of the `line`-th line
+    /// of `source`.
+    fn new(source: &str, line: usize, chars: usize) -> Self {
+        let character = split_lines(source)
+            .nth(line)
+            .map(strip_line_end)
+            .map_or(0, |text| text.chars().take(chars).map(char::len_utf16).sum());
+        Self {
+            character: u32::try_from(character).unwrap_or(u32::MAX),
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+impl TokenSpan<'_> {
+    /// Converts the span to an LSP range, i.e. a start position and an
+    /// exclusive end position.
+    ///
+    /// `source` must be the content of the file the span refers to, as it is
+    /// needed to count UTF-16 code units.
+    ///
+    /// The columns of the span must count code points, as with
+    /// [`lex`](crate::lex): a span lexed with other units, see
+    /// [`lex_with_columns`](crate::lex_with_columns), gives wrong positions.
+    /// With [`ColumnUnit::Utf16`](crate::location::ColumnUnit::Utf16), the
+    /// columns already are LSP characters.
+    #[inline]
+    #[must_use]
+    pub fn to_lsp_range(&self, source: &str) -> (LspPosition, LspPosition) {
+        (
+            LspPosition::new(source, self.start.line(), self.start.col()),
+            LspPosition::new(source, self.end.line(), self.end.col().saturating_add(1)),
+        )
+    }
+}
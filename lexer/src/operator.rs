@@ -1,103 +1,461 @@
 //! Module to define the [`Operator`] type.
 
+use core::fmt;
+
+/// All the operators, sorted by index.
+const OPERATORS: [Operator; 49] = [
+    Operator::Ampersand,
+    Operator::Assign,
+    Operator::BitwiseNot,
+    Operator::BitwiseOr,
+    Operator::BitwiseXor,
+    Operator::BraceClose,
+    Operator::BraceOpen,
+    Operator::BracketClose,
+    Operator::BracketOpen,
+    Operator::Colon,
+    Operator::Comma,
+    Operator::Divide,
+    Operator::Dot,
+    Operator::Gt,
+    Operator::Interrogation,
+    Operator::LogicalNot,
+    Operator::Lt,
+    Operator::Minus,
+    Operator::Modulo,
+    Operator::ParenthesisClose,
+    Operator::ParenthesisOpen,
+    Operator::Plus,
+    Operator::SemiColon,
+    Operator::Star,
+    Operator::AddAssign,
+    Operator::AndAssign,
+    Operator::Arrow,
+    Operator::Decrement,
+    Operator::Different,
+    Operator::DivAssign,
+    Operator::Equal,
+    Operator::Ge,
+    Operator::Increment,
+    Operator::Le,
+    Operator::LogicalAnd,
+    Operator::LogicalOr,
+    Operator::ModAssign,
+    Operator::MulAssign,
+    Operator::OrAssign,
+    Operator::ShiftLeft,
+    Operator::ShiftRight,
+    Operator::SubAssign,
+    Operator::XorAssign,
+    Operator::ShiftLeftAssign,
+    Operator::ShiftRightAssign,
+    Operator::Defined,
+    Operator::Hash,
+    Operator::HashHash,
+    Operator::Ellipsis,
+];
+
 /// Type to represent a symbol
 ///
 /// See [`SymbolState`](crate::lexer::state::api::SymbolState) for more
 /// information.
 #[expect(clippy::arbitrary_source_item_ordering)]
-#[derive(Debug, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "the set of C operators is fixed")]
+///
+/// The discriminants are stable, see [`Operator::index`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Operator {
     // one character
     /// &
-    Ampersand,
+    Ampersand = 0,
     /// =
-    Assign,
+    Assign = 1,
     /// ~
-    BitwiseNot,
+    BitwiseNot = 2,
     /// |
-    BitwiseOr,
+    BitwiseOr = 3,
     /// ^
-    BitwiseXor,
+    BitwiseXor = 4,
     /// }
-    BraceClose,
+    BraceClose = 5,
     /// {
-    BraceOpen,
+    BraceOpen = 6,
     /// ]
-    BracketClose,
+    BracketClose = 7,
     /// [
-    BracketOpen,
+    BracketOpen = 8,
     /// :
-    Colon,
+    Colon = 9,
     /// ,
-    Comma,
+    Comma = 10,
     /// /
-    Divide,
+    Divide = 11,
     /// .
-    Dot,
+    Dot = 12,
     /// >
-    Gt,
+    Gt = 13,
     /// ?
-    Interrogation,
+    Interrogation = 14,
     /// !
-    LogicalNot,
+    LogicalNot = 15,
     /// <
-    Lt,
+    Lt = 16,
     /// -
-    Minus,
+    Minus = 17,
     /// %
-    Modulo,
+    Modulo = 18,
     /// )
-    ParenthesisClose,
+    ParenthesisClose = 19,
     /// (
-    ParenthesisOpen,
+    ParenthesisOpen = 20,
     /// +
-    Plus,
+    Plus = 21,
     /// ;
-    SemiColon,
+    SemiColon = 22,
     /// *
-    Star,
+    Star = 23,
     // two characters
     /// +=
-    AddAssign,
+    AddAssign = 24,
     /// &=
-    AndAssign,
+    AndAssign = 25,
     /// ->
-    Arrow,
+    Arrow = 26,
     /// --
-    Decrement,
+    Decrement = 27,
     /// !=
-    Different,
+    Different = 28,
     /// /=
-    DivAssign,
+    DivAssign = 29,
     /// ==
-    Equal,
+    Equal = 30,
     /// >=
-    Ge,
+    Ge = 31,
     /// ++
-    Increment,
+    Increment = 32,
     /// <=
-    Le,
+    Le = 33,
     /// &&
-    LogicalAnd,
+    LogicalAnd = 34,
     /// ||
-    LogicalOr,
+    LogicalOr = 35,
     /// %=
-    ModAssign,
+    ModAssign = 36,
     /// *=
-    MulAssign,
+    MulAssign = 37,
     /// |=
-    OrAssign,
+    OrAssign = 38,
     /// <<
-    ShiftLeft,
+    ShiftLeft = 39,
     /// >>
-    ShiftRight,
+    ShiftRight = 40,
     /// -=
-    SubAssign,
+    SubAssign = 41,
     /// ^=
-    XorAssign,
+    XorAssign = 42,
     // three characters
     /// <<=
-    ShiftLeftAssign,
+    ShiftLeftAssign = 43,
     /// >>=
-    ShiftRightAssign,
-}
\ No newline at end of file
+    ShiftRightAssign = 44,
+    // preprocessor
+    /// `defined`, only in the expression of an `#if` or `#elif`.
+    Defined = 45,
+    /// `#`, which stringizes a macro parameter, except at the start of a line.
+    Hash = 46,
+    /// `##`, which pastes two tokens in a macro.
+    HashHash = 47,
+    // three characters, after the preprocessor ones to keep the indices
+    /// `...`, of variadic functions and macros.
+    Ellipsis = 48,
+}
+
+impl Operator {
+    /// Returns the operator of the given index, see [`Operator::index`].
+    #[inline]
+    #[must_use]
+    pub fn from_index(index: usize) -> Option<Self> {
+        OPERATORS.get(index).copied()
+    }
+
+    /// Tries to make an operator from its spelling, e.g. `"<<="`.
+    ///
+    /// `"defined"` is an identifier outside of the `#if` and `#elif`
+    /// directives, so it doesn't make [`Operator::Defined`].
+    #[inline]
+    #[must_use]
+    pub fn from_spelling(spelling: &str) -> Option<Self> {
+        match spelling {
+            "&" => Some(Self::Ampersand),
+            "=" => Some(Self::Assign),
+            "~" => Some(Self::BitwiseNot),
+            "|" => Some(Self::BitwiseOr),
+            "^" => Some(Self::BitwiseXor),
+            "}" => Some(Self::BraceClose),
+            "{" => Some(Self::BraceOpen),
+            "]" => Some(Self::BracketClose),
+            "[" => Some(Self::BracketOpen),
+            ":" => Some(Self::Colon),
+            "," => Some(Self::Comma),
+            "/" => Some(Self::Divide),
+            "." => Some(Self::Dot),
+            ">" => Some(Self::Gt),
+            "?" => Some(Self::Interrogation),
+            "!" => Some(Self::LogicalNot),
+            "<" => Some(Self::Lt),
+            "-" => Some(Self::Minus),
+            "%" => Some(Self::Modulo),
+            ")" => Some(Self::ParenthesisClose),
+            "(" => Some(Self::ParenthesisOpen),
+            "+" => Some(Self::Plus),
+            ";" => Some(Self::SemiColon),
+            "*" => Some(Self::Star),
+            "+=" => Some(Self::AddAssign),
+            "&=" => Some(Self::AndAssign),
+            "->" => Some(Self::Arrow),
+            "--" => Some(Self::Decrement),
+            "!=" => Some(Self::Different),
+            "/=" => Some(Self::DivAssign),
+            "==" => Some(Self::Equal),
+            ">=" => Some(Self::Ge),
+            "++" => Some(Self::Increment),
+            "<=" => Some(Self::Le),
+            "&&" => Some(Self::LogicalAnd),
+            "||" => Some(Self::LogicalOr),
+            "%=" => Some(Self::ModAssign),
+            "*=" => Some(Self::MulAssign),
+            "|=" => Some(Self::OrAssign),
+            "<<" => Some(Self::ShiftLeft),
+            ">>" => Some(Self::ShiftRight),
+            "-=" => Some(Self::SubAssign),
+            "^=" => Some(Self::XorAssign),
+            "<<=" => Some(Self::ShiftLeftAssign),
+            ">>=" => Some(Self::ShiftRightAssign),
+            "#" => Some(Self::Hash),
+            "##" => Some(Self::HashHash),
+            "..." => Some(Self::Ellipsis),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the operator, which is also its discriminant.
+    ///
+    /// Indices are stable and go from 0 to the number of operators minus one,
+    /// so they can be used to index tables, e.g. for code generation.
+    #[inline]
+    #[must_use]
+    #[expect(clippy::as_conversions, reason = "fieldless enum to its discriminant")]
+    pub const fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Checks if the operator computes a value from its operands without side
+    /// effects, so it can be folded when they are constants, like `1 + 2`.
+    ///
+    /// Assignments, increments and decrements modify their operand, and member
+    /// accesses, subscripts and calls need more than constant operands, so they
+    /// are not foldable. Nor are punctuators.
+    #[inline]
+    #[must_use]
+    pub const fn is_foldable(&self) -> bool {
+        match self {
+            Self::Ampersand
+            | Self::BitwiseNot
+            | Self::BitwiseOr
+            | Self::BitwiseXor
+            | Self::Colon
+            | Self::Different
+            | Self::Divide
+            | Self::Equal
+            | Self::Ge
+            | Self::Gt
+            | Self::Interrogation
+            | Self::Le
+            | Self::LogicalAnd
+            | Self::LogicalNot
+            | Self::LogicalOr
+            | Self::Lt
+            | Self::Minus
+            | Self::Modulo
+            | Self::Plus
+            | Self::ShiftLeft
+            | Self::ShiftRight
+            | Self::Star => true,
+            Self::AddAssign
+            | Self::AndAssign
+            | Self::Arrow
+            | Self::Assign
+            | Self::BraceClose
+            | Self::BraceOpen
+            | Self::BracketClose
+            | Self::BracketOpen
+            | Self::Comma
+            | Self::Decrement
+            | Self::Defined
+            | Self::DivAssign
+            | Self::Dot
+            | Self::Ellipsis
+            | Self::Hash
+            | Self::HashHash
+            | Self::Increment
+            | Self::ModAssign
+            | Self::MulAssign
+            | Self::OrAssign
+            | Self::ParenthesisClose
+            | Self::ParenthesisOpen
+            | Self::SemiColon
+            | Self::ShiftLeftAssign
+            | Self::ShiftRightAssign
+            | Self::SubAssign
+            | Self::XorAssign => false,
+        }
+    }
+
+    /// Checks if the operator can be used as a postfix unary operator, like
+    /// `i++`.
+    #[inline]
+    #[must_use]
+    pub const fn is_postfix_unary(&self) -> bool {
+        matches!(self, Self::Decrement | Self::Increment)
+    }
+
+    /// Checks if the operator can be used as a prefix unary operator, like
+    /// `-a`, `*ptr` (dereference) or `&a` (address-of).
+    #[inline]
+    #[must_use]
+    pub const fn is_prefix_unary(&self) -> bool {
+        matches!(
+            self,
+            Self::Ampersand
+                | Self::BitwiseNot
+                | Self::Decrement
+                | Self::Defined
+                | Self::Increment
+                | Self::LogicalNot
+                | Self::Minus
+                | Self::Plus
+                | Self::Star
+        )
+    }
+
+    /// Returns the binding strength of the operator, or [`None`] if the
+    /// operator is only a punctuator (e.g. `;` or `}`).
+    ///
+    /// The levels follow the [C operator precedence](https://en.cppreference.com/w/c/language/operator_precedence)
+    /// chart, numbered the other way around: the higher the value, the tighter
+    /// the operator binds. Hence the comma operator has the lowest value, the
+    /// assignment operators are just above it, then comes the conditional
+    /// operator.
+    ///
+    /// When an operator can be both unary and binary (e.g. `*` or `-`), the
+    /// binary precedence is returned.
+    #[inline]
+    #[must_use]
+    pub const fn precedence(&self) -> Option<u8> {
+        match self {
+            Self::BraceClose
+            | Self::BraceOpen
+            | Self::BracketClose
+            | Self::Colon
+            | Self::Ellipsis
+            | Self::ParenthesisClose
+            | Self::Hash
+            | Self::HashHash
+            | Self::SemiColon => None,
+            Self::Comma => Some(1),
+            Self::AddAssign
+            | Self::AndAssign
+            | Self::Assign
+            | Self::DivAssign
+            | Self::ModAssign
+            | Self::MulAssign
+            | Self::OrAssign
+            | Self::ShiftLeftAssign
+            | Self::ShiftRightAssign
+            | Self::SubAssign
+            | Self::XorAssign => Some(2),
+            Self::Interrogation => Some(3),
+            Self::LogicalOr => Some(4),
+            Self::LogicalAnd => Some(5),
+            Self::BitwiseOr => Some(6),
+            Self::BitwiseXor => Some(7),
+            Self::Ampersand => Some(8),
+            Self::Different | Self::Equal => Some(9),
+            Self::Ge | Self::Gt | Self::Le | Self::Lt => Some(10),
+            Self::ShiftLeft | Self::ShiftRight => Some(11),
+            Self::Minus | Self::Plus => Some(12),
+            Self::Divide | Self::Modulo | Self::Star => Some(13),
+            Self::BitwiseNot | Self::Defined | Self::LogicalNot => Some(14),
+            Self::Arrow
+            | Self::BracketOpen
+            | Self::Decrement
+            | Self::Dot
+            | Self::Increment
+            | Self::ParenthesisOpen => Some(15),
+        }
+    }
+
+    /// Returns the spelling of the operator, e.g. `"<<="`.
+    #[inline]
+    #[must_use]
+    pub const fn spelling(&self) -> &'static str {
+        match self {
+            Self::Ampersand => "&",
+            Self::Assign => "=",
+            Self::BitwiseNot => "~",
+            Self::BitwiseOr => "|",
+            Self::BitwiseXor => "^",
+            Self::BraceClose => "}",
+            Self::BraceOpen => "{",
+            Self::BracketClose => "]",
+            Self::BracketOpen => "[",
+            Self::Colon => ":",
+            Self::Comma => ",",
+            Self::Divide => "/",
+            Self::Dot => ".",
+            Self::Gt => ">",
+            Self::Interrogation => "?",
+            Self::LogicalNot => "!",
+            Self::Lt => "<",
+            Self::Minus => "-",
+            Self::Modulo => "%",
+            Self::ParenthesisClose => ")",
+            Self::ParenthesisOpen => "(",
+            Self::Plus => "+",
+            Self::SemiColon => ";",
+            Self::Star => "*",
+            Self::AddAssign => "+=",
+            Self::AndAssign => "&=",
+            Self::Arrow => "->",
+            Self::Decrement => "--",
+            Self::Different => "!=",
+            Self::DivAssign => "/=",
+            Self::Equal => "==",
+            Self::Ge => ">=",
+            Self::Increment => "++",
+            Self::Le => "<=",
+            Self::LogicalAnd => "&&",
+            Self::LogicalOr => "||",
+            Self::ModAssign => "%=",
+            Self::MulAssign => "*=",
+            Self::OrAssign => "|=",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::SubAssign => "-=",
+            Self::XorAssign => "^=",
+            Self::ShiftLeftAssign => "<<=",
+            Self::ShiftRightAssign => ">>=",
+            Self::Defined => "defined",
+            Self::Hash => "#",
+            Self::HashHash => "##",
+            Self::Ellipsis => "...",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.spelling().fmt(f)
+    }
+}
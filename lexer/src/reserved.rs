@@ -0,0 +1,30 @@
+//! Module to classify reserved spellings of the language.
+
+use crate::keyword::{Keyword, TryKeyword};
+use crate::operator::Operator;
+
+/// Kind of a reserved spelling, as returned by [`reserved_kind`].
+#[derive(Debug, PartialEq, Eq)]
+#[expect(
+    clippy::exhaustive_enums,
+    reason = "a spelling is a keyword or an operator"
+)]
+pub enum ReservedKind {
+    /// The spelling is a keyword, deprecated or not.
+    Keyword(Keyword),
+    /// The spelling is an operator or a punctuator.
+    Operator(Operator),
+}
+
+/// Classifies `value` as a keyword or an operator spelling.
+///
+/// Returns [`None`] if `value` is neither, like an identifier.
+#[inline]
+#[must_use]
+pub fn reserved_kind(value: &str) -> Option<ReservedKind> {
+    match Keyword::from_value_or_res(value) {
+        TryKeyword::Deprecated(keyword) | TryKeyword::Success(keyword) =>
+            Some(ReservedKind::Keyword(keyword)),
+        TryKeyword::Failure => Operator::from_spelling(value).map(ReservedKind::Operator),
+    }
+}
@@ -0,0 +1,22 @@
+//! Module to define the [`Standard`] type.
+
+/// Revisions of the C standard.
+///
+/// The order of the variants is chronological, so standards can be compared
+/// to know if a feature is available.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+#[expect(clippy::arbitrary_source_item_ordering, reason = "chronological order")]
+pub enum Standard {
+    /// ANSI C, also known as C90.
+    C89,
+    /// ISO/IEC 9899:1999.
+    C99,
+    /// ISO/IEC 9899:2011.
+    C11,
+    /// ISO/IEC 9899:2018, only a bug-fix release of C11.
+    C17,
+    /// ISO/IEC 9899:2024.
+    #[default]
+    C23,
+}
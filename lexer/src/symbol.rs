@@ -1,17 +1,121 @@
-use std::collections::HashSet;
+//! Module to define the [`SymbolTable`] type.
+
+use std::collections::HashMap;
+
+use crate::location::Location;
 
 pub struct Symbol {
 
 }
 
+/// Symbol declared in a scope.
 pub struct SymbolTableEntry {
-    symbol_type: String,
-    scope: String,
     address: String,
-    value: String,
+    /// Location of the declaration.
+    location: Location,
+    /// Name of the symbol.
+    name: String,
+    /// Locations where the symbol is used, see [`SymbolTable::reference`].
+    references: Vec<Location>,
     rwx: String,
+    scope: String,
+    symbol_type: String,
+    value: String,
+}
+
+impl SymbolTableEntry {
+    /// Returns the location where the symbol was declared.
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Makes a new entry for a symbol declared at `location`.
+    #[inline]
+    #[must_use]
+    pub const fn new(name: String, symbol_type: String, scope: String, location: Location) -> Self {
+        Self {
+            address: String::new(),
+            location,
+            name,
+            references: Vec::new(),
+            rwx: String::new(),
+            scope,
+            symbol_type,
+            value: String::new(),
+        }
+    }
+
+    /// Returns the locations where the symbol is used, in order.
+    #[inline]
+    #[must_use]
+    pub fn references(&self) -> &[Location] {
+        &self.references
+    }
 }
 
+/// Error returned when declaring a symbol that already exists in the same
+/// scope.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Redeclaration {
+    /// Location of the first declaration, for "first declared here".
+    pub first: Location,
+    /// Name of the symbol.
+    pub name: String,
+    /// Location of the redeclaration.
+    pub second: Location,
+}
+
+/// Table of the declared symbols, by scope.
+#[derive(Default)]
 pub struct SymbolTable {
-    set: HashSet<SymbolTableEntry>
-}
\ No newline at end of file
+    /// Entries by scope and name.
+    set: HashMap<(String, String), SymbolTableEntry>,
+}
+
+impl SymbolTable {
+    /// Scope of the symbols visible in the whole file, like the predefined
+    /// macros.
+    pub const FILE_SCOPE: &'static str = "";
+
+    /// Declares a new symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Redeclaration`] with both locations if a symbol with the
+    /// same name was already declared in the same scope. The table is left
+    /// unchanged.
+    #[inline]
+    pub fn declare(&mut self, entry: SymbolTableEntry) -> Result<(), Redeclaration> {
+        let key = (entry.scope.clone(), entry.name.clone());
+        if let Some(first) = self.set.get(&key) {
+            return Err(Redeclaration {
+                first: first.location.clone(),
+                name: entry.name,
+                second: entry.location,
+            });
+        }
+        self.set.insert(key, entry);
+        Ok(())
+    }
+
+    /// Returns the symbol named `name` declared in `scope`, if any.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, scope: &str, name: &str) -> Option<&SymbolTableEntry> {
+        self.set.get(&(scope.to_owned(), name.to_owned()))
+    }
+
+    /// Records a use at `location` of the symbol named `name` declared in
+    /// `scope`. Returns `false` if there is no such symbol.
+    #[inline]
+    pub fn reference(&mut self, scope: &str, name: &str, location: Location) -> bool {
+        let Some(entry) = self.set.get_mut(&(scope.to_owned(), name.to_owned())) else {
+            return false;
+        };
+        entry.references.push(location);
+        true
+    }
+}
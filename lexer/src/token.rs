@@ -1,10 +1,64 @@
+//! Module to define the [`Token`] type.
+
+use core::fmt;
+
 use crate::keyword::Keyword;
 use crate::literal::Literal;
 use crate::operator::Operator;
 
+/// Token produced by the lexer.
+#[derive(Debug, PartialEq)]
+#[expect(clippy::exhaustive_enums, reason = "consumers must handle every token")]
 pub enum Token {
+    /// Preprocessor directive, like `#if`. It is followed by the tokens of
+    /// the directive, up to the end of the line.
+    Directive(String),
+    /// End of the input, always the last token of a stream.
+    Eof,
+    /// Invalid source, only produced by
+    /// [`lex_recovering`](crate::lex_recovering).
+    Error,
+    /// Literal: number, char, string...
     Literal(Literal),
+    /// Keyword of the language, see [`Keyword`].
     Keyword(Keyword),
+    /// Operator or punctuator, see [`Operator`].
     Operator(Operator),
+    /// Identifier.
     Symbol(String),
-}
\ No newline at end of file
+}
+
+impl Token {
+    /// Returns the name of the kind of the token, in upper case (e.g. `INT`).
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Literal(Literal::Char(..)) => "CHAR",
+            Self::Literal(Literal::Float(..)) => "FLOAT",
+            Self::Literal(Literal::Int(..)) => "INT",
+            Self::Literal(Literal::String(..)) => "STRING",
+            Self::Keyword(_) => "KEYWORD",
+            Self::Operator(_) => "OPERATOR",
+            Self::Symbol(_) => "SYMBOL",
+            Self::Directive(_) => "DIRECTIVE",
+            Self::Eof => "EOF",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(literal) => literal.fmt(f),
+            Self::Keyword(keyword) => keyword.fmt(f),
+            Self::Operator(operator) => operator.fmt(f),
+            Self::Symbol(symbol) => symbol.fmt(f),
+            Self::Directive(name) => write!(f, "#{name}"),
+            Self::Eof => "<eof>".fmt(f),
+            Self::Error => "<error>".fmt(f),
+        }
+    }
+}
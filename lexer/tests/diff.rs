@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use lexer::diff::{TokenDiff, token_diff};
+use lexer::lex;
+
+#[test]
+fn one_inserted_token() {
+    let path = Path::new("a.c");
+    let old = lex(path, "int foo bar".lines()).unwrap();
+    let new = lex(path, "int foo baz bar".lines()).unwrap();
+    assert_eq!(token_diff(&old, &new, false), [TokenDiff::Added(&new[2])]);
+    // With spans, `bar` and the end of file moved as well
+    assert_eq!(
+        token_diff(&old, &new, true),
+        [
+            TokenDiff::Changed { new: &new[2], old: &old[2] },
+            TokenDiff::Changed { new: &new[3], old: &old[3] },
+            TokenDiff::Added(&new[4])
+        ]
+    );
+}
+
+#[test]
+fn changed_and_removed_tokens() {
+    let path = Path::new("a.c");
+    let old = lex(path, "a b c d".lines()).unwrap();
+    let new = lex(path, "a x c".lines()).unwrap();
+    assert_eq!(
+        token_diff(&old, &new, true),
+        [
+            TokenDiff::Changed { new: &new[1], old: &old[1] },
+            TokenDiff::Changed { new: &new[3], old: &old[3] },
+            TokenDiff::Removed(&old[4])
+        ]
+    );
+    // Without spans, `c` and the end of file match
+    assert_eq!(
+        token_diff(&old, &new, false),
+        [
+            TokenDiff::Changed { new: &new[1], old: &old[1] },
+            TokenDiff::Removed(&old[3])
+        ]
+    );
+    assert!(token_diff(&old, &old, true).is_empty());
+}
+
+#[test]
+fn long_streams_with_small_edit() {
+    let path = Path::new("a.c");
+    let source = "x = y + 1;\n".repeat(10_000);
+    let old = lex(path, source.lines()).unwrap();
+    let new = lex(path, source.replacen("y", "z", 1).lines()).unwrap();
+    assert_eq!(
+        token_diff(&old, &new, true),
+        [TokenDiff::Changed { new: &new[2], old: &old[2] }]
+    );
+}
@@ -0,0 +1,155 @@
+use std::iter::{self, Peekable};
+use std::path::Path;
+use std::str::Chars;
+
+use lexer::keyword::Keyword;
+use lexer::location::Location;
+use lexer::operator::Operator;
+use lexer::token::Token;
+use lexer::{
+    LToken, TokenSpan, annotate, dump_tokens, lex, lex_str, token_histogram, write_tokens,
+    write_tokens_jsonl,
+};
+
+fn token(line: usize, col: usize, token: Token) -> LToken<'static> {
+    let location = Location::new(line, col);
+    let span = TokenSpan { filepath: None, start: location.clone(), end: location };
+    LToken { id: 0, preprocessor: false, span, token }
+}
+
+/// Decodes the fields of a flat JSON object, like `{"a":1,"b":"c"}`, with the
+/// numbers as strings.
+fn json_fields(object: &str) -> Vec<(String, String)> {
+    let mut chars = object.strip_prefix('{').unwrap().chars().peekable();
+    let mut fields = vec![];
+    loop {
+        assert_eq!(chars.next(), Some('"'));
+        let key = json_string(&mut chars);
+        assert_eq!(chars.next(), Some(':'));
+        let value = if chars.next_if_eq(&'"').is_some() {
+            json_string(&mut chars)
+        } else {
+            iter::from_fn(|| chars.next_if(char::is_ascii_digit)).collect()
+        };
+        fields.push((key, value));
+        match chars.next() {
+            Some(',') => (),
+            Some('}') if chars.peek().is_none() => return fields,
+            other => panic!("unexpected {other:?} in {object}"),
+        }
+    }
+}
+
+/// Decodes a JSON string, after its opening quote, up to its closing quote.
+fn json_string(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut string = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => return string,
+            '\\' => match chars.next().unwrap() {
+                'n' => string.push('\n'),
+                't' => string.push('\t'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    string.push(char::from_u32(u32::from_str_radix(&hex, 16).unwrap()).unwrap());
+                }
+                escaped => string.push(escaped),
+            },
+            _ => string.push(ch),
+        }
+    }
+    panic!("unterminated JSON string")
+}
+
+#[test]
+fn write_matches_dump() {
+    let tokens = [
+        token(0, 0, Token::Keyword(Keyword::Int)),
+        token(0, 4, Token::Symbol("foo".into())),
+        token(0, 7, Token::Operator(Operator::SemiColon)),
+    ];
+    let mut buffer = vec![];
+    write_tokens(&mut buffer, &tokens).unwrap();
+    let dump = dump_tokens(&tokens);
+    assert_eq!(String::from_utf8(buffer).unwrap(), dump);
+    assert_eq!(dump, "1:1\tint\n1:5\tfoo\n1:8\t;\n");
+}
+
+#[test]
+fn histogram_counts_each_token() {
+    let tokens = [
+        token(0, 0, Token::Keyword(Keyword::Int)),
+        token(0, 4, Token::Symbol("a".into())),
+        token(0, 5, Token::Operator(Operator::SemiColon)),
+        token(1, 0, Token::Keyword(Keyword::Int)),
+        token(1, 4, Token::Symbol("b".into())),
+        token(1, 5, Token::Operator(Operator::SemiColon)),
+        token(2, 0, Token::Keyword(Keyword::Return)),
+        token(2, 7, Token::Symbol("a".into())),
+        token(2, 8, Token::Operator(Operator::SemiColon)),
+    ];
+    let histogram = token_histogram(&tokens);
+    let counts: Vec<_> = histogram
+        .iter()
+        .map(|(token, count)| (token.as_str(), *count))
+        .collect();
+    assert_eq!(counts, [(";", 3), ("a", 2), ("b", 1), ("int", 2), ("return", 1)]);
+}
+
+#[test]
+fn annotate_appends_kinds() {
+    let source = "foo 12\n  bar 3.5 0x1f\n";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    assert_eq!(
+        annotate(source, &tokens),
+        "foo[SYMBOL] 12[INT]\n  bar[SYMBOL] 3.5[FLOAT] 0x1f[INT]\n"
+    );
+
+    let source = "a\rb 1\r\nc";
+    let tokens = lex_str(Path::new("a.c"), source).unwrap();
+    assert_eq!(annotate(source, &tokens), "a[SYMBOL]\rb[SYMBOL] 1[INT]\r\nc[SYMBOL]");
+
+    let source = "\"é\" 'ü' x";
+    let tokens = lex_str(Path::new("a.c"), source).unwrap();
+    assert_eq!(annotate(source, &tokens), "\"é\"[STRING] 'ü'[CHAR] x[SYMBOL]");
+}
+
+#[test]
+fn jsonl_one_object_per_line() {
+    let tokens = lex(Path::new("a.c"), "x = \"a\\\"b\";".lines()).unwrap();
+    let mut buffer = vec![];
+    write_tokens_jsonl(&mut buffer, &tokens).unwrap();
+    let jsonl = String::from_utf8(buffer).unwrap();
+    let lines: Vec<_> = jsonl.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r#"{"line":1,"col":1,"end_line":1,"end_col":1,"kind":"SYMBOL","text":"x"}"#,
+            r#"{"line":1,"col":3,"end_line":1,"end_col":3,"kind":"OPERATOR","text":"="}"#,
+            r#"{"line":1,"col":5,"end_line":1,"end_col":10,"kind":"STRING","text":"\"a\\\"b\""}"#,
+            r#"{"line":1,"col":11,"end_line":1,"end_col":11,"kind":"OPERATOR","text":";"}"#,
+            r#"{"line":1,"col":12,"end_line":1,"end_col":12,"kind":"EOF","text":"<eof>"}"#,
+        ]
+    );
+
+    let source = "s = \"\\tb\\\\\";\n  '\\n'";
+    let tokens = lex_str(Path::new("a.c"), source).unwrap();
+    let mut buffer = vec![];
+    write_tokens_jsonl(&mut buffer, &tokens).unwrap();
+    let jsonl = String::from_utf8(buffer).unwrap();
+    assert_eq!(jsonl.lines().count(), tokens.len());
+    for (line, token) in jsonl.lines().zip(&tokens) {
+        let (start_line, start_col) = token.span.start.human();
+        let (end_line, end_col) = token.span.end.human();
+        let fields = [
+            ("line", start_line.to_string()),
+            ("col", start_col.to_string()),
+            ("end_line", end_line.to_string()),
+            ("end_col", end_col.to_string()),
+            ("kind", token.token.kind().to_string()),
+            ("text", token.token.to_string()),
+        ];
+        let fields = fields.map(|(key, value)| (key.to_owned(), value));
+        assert_eq!(json_fields(line), fields, "{line}");
+    }
+}
@@ -0,0 +1,18 @@
+use lexer::indent::{IndentStyle, detect_indent};
+
+#[test]
+fn two_spaces() {
+    let source = "int main() {\n  if (a) {\n    return 1;\n  }\n\n  return 0;\n}\n";
+    assert_eq!(detect_indent(source), IndentStyle::Spaces(2));
+}
+
+#[test]
+fn tabs() {
+    let source = "int main() {\n\tif (a) {\n\t\treturn 1;\n\t}\n}\n";
+    assert_eq!(detect_indent(source), IndentStyle::Tabs);
+}
+
+#[test]
+fn no_indentation() {
+    assert_eq!(detect_indent("int a;\nint b;\n"), IndentStyle::Unknown);
+}
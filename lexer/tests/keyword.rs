@@ -0,0 +1,114 @@
+use lexer::keyword::{Keyword, KeywordType, TryKeyword};
+use lexer::standard::Standard;
+
+#[test]
+fn static_assert_depends_on_standard() {
+    assert!(matches!(
+        Keyword::from_value_for("_Static_assert", Standard::C11),
+        TryKeyword::Success(Keyword::UStaticAssert)
+    ));
+    assert!(matches!(
+        Keyword::from_value_for("_Static_assert", Standard::C89),
+        TryKeyword::Failure
+    ));
+    assert!(matches!(
+        Keyword::from_value_for("_Static_assert", Standard::C23),
+        TryKeyword::Deprecated(Keyword::UStaticAssert)
+    ));
+}
+
+#[test]
+fn underscore_keywords_are_recognised() {
+    for (value, standard) in [
+        ("_Bool", Standard::C99),
+        ("_Complex", Standard::C99),
+        ("_Imaginary", Standard::C99),
+        ("_Atomic", Standard::C11),
+        ("_Noreturn", Standard::C11),
+        ("_Thread_local", Standard::C11),
+        ("_Static_assert", Standard::C11),
+        ("_Generic", Standard::C11),
+        ("_Alignas", Standard::C11),
+        ("_Alignof", Standard::C11),
+    ] {
+        let TryKeyword::Success(keyword) = Keyword::from_value_for(value, standard) else {
+            panic!("{value} should be a keyword in {standard:?}")
+        };
+        assert_eq!(keyword.standard(), standard);
+        assert_eq!(keyword.to_string(), value);
+    }
+    assert!(matches!(
+        Keyword::from_value_for("restrict", Standard::C89),
+        TryKeyword::Failure
+    ));
+    assert!(matches!(
+        Keyword::from_value_for("int", Standard::C89),
+        TryKeyword::Success(Keyword::Int)
+    ));
+}
+
+#[test]
+fn gnu_spellings() {
+    assert!(matches!(
+        Keyword::from_gnu_value("__inline__", Standard::C89),
+        TryKeyword::Success(Keyword::Inline)
+    ));
+    assert!(matches!(
+        Keyword::from_value_for("__inline__", Standard::C89),
+        TryKeyword::Failure
+    ));
+    assert!(matches!(
+        Keyword::from_gnu_value("__typeof__", Standard::C11),
+        TryKeyword::Success(Keyword::Typeof)
+    ));
+    assert!(matches!(
+        Keyword::from_gnu_value("__extension__", Standard::C99),
+        TryKeyword::Success(Keyword::GnuExtension)
+    ));
+    assert!(matches!(Keyword::from_value_or_res("__extension__"), TryKeyword::Failure));
+    assert!(matches!(
+        Keyword::from_gnu_value("while", Standard::C99),
+        TryKeyword::Success(Keyword::While)
+    ));
+    assert!(matches!(Keyword::from_gnu_value("__foo__", Standard::C99), TryKeyword::Failure));
+    assert_eq!(Keyword::GnuExtension.to_string(), "__extension__");
+}
+
+#[test]
+fn all_c23_keywords_are_classified() {
+    let keywords = "alignas alignof auto bool break case char const constexpr continue default do \
+        double else enum extern false float for goto if inline int long nullptr register restrict \
+        return short signed sizeof static static_assert struct switch thread_local true typedef \
+        typeof typeof_unqual union unsigned void volatile while _Alignas _Alignof _Atomic _BitInt \
+        _Bool _Complex _Decimal128 _Decimal32 _Decimal64 _Generic _Imaginary _Noreturn \
+        _Static_assert _Thread_local";
+    for value in keywords.split_whitespace() {
+        let (TryKeyword::Success(keyword) | TryKeyword::Deprecated(keyword)) =
+            Keyword::from_value_for(value, Standard::C23)
+        else {
+            panic!("{value} should be a keyword in C23")
+        };
+        assert_eq!(keyword.to_string(), value);
+    }
+    for (value, standard, keyword_type) in [
+        ("inline", Standard::C99, KeywordType::Storage),
+        ("register", Standard::C89, KeywordType::Storage),
+        ("volatile", Standard::C89, KeywordType::Storage),
+        ("unsigned", Standard::C89, KeywordType::Type),
+        ("typedef", Standard::C89, KeywordType::Storage),
+        ("goto", Standard::C89, KeywordType::Control),
+        ("_BitInt", Standard::C23, KeywordType::Type),
+    ] {
+        let TryKeyword::Success(keyword) = Keyword::from_value_for(value, standard) else {
+            panic!("{value} should be a keyword in {standard:?}")
+        };
+        assert_eq!((keyword.standard(), keyword.keyword_type()), (standard, keyword_type));
+    }
+    // Macros of the standard library, not keywords
+    for value in ["NULL", "__bool_true_false_are_defined", "EOF"] {
+        assert!(
+            matches!(Keyword::from_value_for(value, Standard::C23), TryKeyword::Failure),
+            "{value} should not be a keyword"
+        );
+    }
+}
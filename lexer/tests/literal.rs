@@ -0,0 +1,33 @@
+use lexer::literal::{Encoding, IntSuffix, Literal};
+
+#[test]
+fn char_int_value() {
+    assert_eq!(Literal::Char('A', Encoding::Plain).as_int_value(), Some(65));
+    assert_eq!(Literal::Char('\0', Encoding::Plain).as_int_value(), Some(0));
+    assert_eq!(Literal::Int(12, IntSuffix::Int).as_int_value(), Some(12));
+    assert_eq!(Literal::String("A".into(), Encoding::Plain).as_int_value(), None);
+}
+
+#[test]
+fn multichar_value() {
+    assert_eq!(Literal::multichar_value("AB"), Some(0x4142));
+    assert_eq!(Literal::multichar_value("ABCDE"), Some(0x4243_4445));
+    assert_eq!(Literal::multichar_value("é"), None);
+}
+
+#[test]
+fn display_c_escapes() {
+    for (literal, spelling) in [
+        (Literal::Char('\0', Encoding::Plain), r"'\0'"),
+        (Literal::Char('\'', Encoding::Plain), r"'\''"),
+        (Literal::Char('"', Encoding::Plain), "'\"'"),
+        (Literal::Char('\x1B', Encoding::Plain), r"'\x1B'"),
+        (Literal::Char('é', Encoding::Wide), r"L'\u00E9'"),
+        (Literal::Char('😀', Encoding::Utf32), r"U'\U0001F600'"),
+        (Literal::String("a\tb\\\"'".into(), Encoding::Plain), r#""a\tb\\\"'""#),
+        (Literal::String("\x01a\0\x32".into(), Encoding::Plain), r#""\x01\141\0\062""#),
+        (Literal::String("\x7F\u{FFFF}".into(), Encoding::Utf16), r#"u"\x7F\uFFFF""#),
+    ] {
+        assert_eq!(literal.to_string(), spelling);
+    }
+}
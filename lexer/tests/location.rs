@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use lexer::lex;
+use lexer::location::Location;
+
+#[test]
+fn same_line() {
+    let first = Location::new(3_usize, 2_usize);
+    let second = Location::new(3_usize, 7_usize);
+    assert!(first.is_before(&second));
+    assert!(second.is_after(&first));
+    assert!(!first.is_after(&second));
+    assert!(!first.is_before(&first.clone()));
+    assert!(!first.is_after(&first.clone()));
+}
+
+#[test]
+fn cross_line() {
+    let first = Location::new(1_usize, 40_usize);
+    let second = Location::new(2_usize, 0_usize);
+    assert!(first.is_before(&second));
+    assert!(second.is_after(&first));
+    assert!(!second.is_before(&first));
+}
+
+#[test]
+fn lexed_offsets() {
+    let source = "ab\n  \"é\" = 'd';\n\n+";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    let offsets: Vec<_> = tokens
+        .iter()
+        .map(|token| token.span.start.offset())
+        .collect();
+    assert!(offsets.is_sorted());
+    for token in &tokens[..tokens.len() - 1] {
+        assert!(source[token.span.start.offset()..].starts_with(&token.token.to_string()[..1]));
+    }
+    assert_eq!(offsets, [0, 5, 10, 12, 15, 18, 19]);
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "lsp")]
+
+use lexer::TokenSpan;
+use lexer::location::Location;
+use lexer::lsp::LspPosition;
+
+#[test]
+fn utf16_columns_after_emoji() {
+    let source = "int a;\n\"😀\" + foo;\n";
+    let span = TokenSpan {
+        filepath: None,
+        start: Location::new(1_usize, 6_usize),
+        end: Location::new(1_usize, 8_usize),
+    };
+    let (start, end) = span.to_lsp_range(source);
+    assert_eq!((start.line, start.character), (1, 7));
+    assert_eq!((end.line, end.character), (1, 10));
+
+    let source = "int a;\r\"😀\" + foo;\r";
+    let (start, end) = span.to_lsp_range(source);
+    assert_eq!((start.line, start.character), (1, 7));
+    assert_eq!((end.line, end.character), (1, 10));
+}
+
+#[test]
+fn ascii_columns_are_unchanged() {
+    let source = "int a;";
+    let span = TokenSpan {
+        filepath: None,
+        start: Location::new(0_usize, 4_usize),
+        end: Location::new(0_usize, 4_usize),
+    };
+    assert_eq!(
+        span.to_lsp_range(source),
+        (LspPosition { character: 4, line: 0 }, LspPosition { character: 5, line: 0 })
+    );
+}
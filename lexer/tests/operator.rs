@@ -0,0 +1,57 @@
+use lexer::operator::Operator;
+
+#[test]
+fn comma_has_lowest_precedence() {
+    let comma = Operator::Comma.precedence().unwrap();
+    let assign = Operator::Assign.precedence().unwrap();
+    assert!(comma < assign);
+    assert_eq!(assign, comma + 1);
+    assert!(assign < Operator::Interrogation.precedence().unwrap());
+    assert_eq!(Operator::ShiftLeftAssign.precedence(), Some(assign));
+}
+
+#[test]
+fn precedence_follows_c_chart() {
+    let star = Operator::Star.precedence().unwrap();
+    let plus = Operator::Plus.precedence().unwrap();
+    let shift = Operator::ShiftLeft.precedence().unwrap();
+    let or = Operator::LogicalOr.precedence().unwrap();
+    assert!(star > plus && plus > shift && shift > or);
+    assert_eq!(Operator::SemiColon.precedence(), None);
+}
+
+#[test]
+fn index_round_trip() {
+    let operators: Vec<_> = (0..).map_while(Operator::from_index).collect();
+    assert_eq!(operators.len(), 49);
+    for (index, operator) in operators.iter().enumerate() {
+        assert_eq!(operator.index(), index);
+    }
+    assert_eq!(Operator::from_index(0), Some(Operator::Ampersand));
+    assert_eq!(Operator::ShiftRightAssign.index(), 44);
+    assert_eq!(Operator::from_index(49), None);
+}
+
+#[test]
+fn unary_operators() {
+    assert!(Operator::Star.is_prefix_unary());
+    assert!(!Operator::Star.is_postfix_unary());
+    assert!(Operator::Increment.is_prefix_unary());
+    assert!(Operator::Increment.is_postfix_unary());
+    assert!(Operator::Decrement.is_postfix_unary());
+    assert!(Operator::Ampersand.is_prefix_unary());
+    assert!(!Operator::Divide.is_prefix_unary());
+    assert!(!Operator::Plus.is_postfix_unary());
+}
+
+#[test]
+fn foldable_operators() {
+    for spelling in [
+        "+", "-", "*", "/", "%", "&", "|", "^", "~", "<<", ">>", "==", "!", "&&",
+    ] {
+        assert!(Operator::from_spelling(spelling).unwrap().is_foldable(), "{spelling}");
+    }
+    for spelling in ["=", "+=", "<<=", "++", "--", "->", ";", ","] {
+        assert!(!Operator::from_spelling(spelling).unwrap().is_foldable(), "{spelling}");
+    }
+}
@@ -0,0 +1,23 @@
+use lexer::keyword::Keyword;
+use lexer::operator::Operator;
+use lexer::reserved::{ReservedKind, reserved_kind};
+
+#[test]
+fn keywords_and_operators() {
+    assert_eq!(reserved_kind("int"), Some(ReservedKind::Keyword(Keyword::Int)));
+    assert_eq!(reserved_kind("_Bool"), Some(ReservedKind::Keyword(Keyword::UBool)));
+    assert_eq!(reserved_kind("&&"), Some(ReservedKind::Operator(Operator::LogicalAnd)));
+    assert_eq!(reserved_kind("<<="), Some(ReservedKind::Operator(Operator::ShiftLeftAssign)));
+    assert_eq!(reserved_kind("foo"), None);
+    assert_eq!(reserved_kind("defined"), None);
+    assert_eq!(reserved_kind("&&&"), None);
+}
+
+#[test]
+fn operator_spelling_round_trip() {
+    for spelling in ["&", "->", ">>=", ";", "?", "!="] {
+        let operator = Operator::from_spelling(spelling).unwrap();
+        assert_eq!(operator.spelling(), spelling);
+        assert_eq!(operator.to_string(), spelling);
+    }
+}
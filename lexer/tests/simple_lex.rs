@@ -1,4 +1,823 @@
-#[tests]
+use std::fs;
+use std::path::Path;
+
+use lexer::error::{LexError, LexErrorKind};
+use lexer::keyword::Keyword;
+use lexer::literal::{Encoding, FloatSuffix, IntSuffix, Literal};
+use lexer::location::{ColumnUnit, Location};
+use lexer::operator::Operator;
+use lexer::standard::Standard;
+use lexer::token::Token;
+use lexer::{
+    concat_adjacent_strings, diagnostics_by_file, is_valid_pp_tokens, lex, lex_file,
+    lex_recovering, lex_str, lex_with_budget, lex_with_columns, lex_with_gnu, lex_with_standard,
+    spawn_lexer, string_literals,
+};
+
+#[test]
 fn lex_hello_world() {
-    todo!()
-}
\ No newline at end of file
+    let source = r#"#include <stdio.h>
+
+int main(void) {
+    printf("Hello, world!\n");
+    return 0;
+}
+"#;
+    let tokens = lex(Path::new("hello.c"), source.lines()).unwrap();
+    assert_eq!(tokens.iter().filter(|token| token.preprocessor).count(), 6);
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(tokens[..6].join(" "), "#include < stdio . h >");
+    assert_eq!(
+        tokens[6..].join(" "),
+        r#"int main ( void ) { printf ( "Hello, world!\n" ) ; return 0 ; } <eof>"#
+    );
+}
+
+#[test]
+fn lex_identifiers() {
+    let path = Path::new("a.c");
+    let tokens = lex(path, "int foo\n  bar_2".lines()).unwrap();
+    let symbols: Vec<_> = tokens.iter().map(|token| &token.token).collect();
+    assert_eq!(
+        symbols,
+        [
+            &Token::Keyword(Keyword::Int),
+            &Token::Symbol("foo".into()),
+            &Token::Symbol("bar_2".into()),
+            &Token::Eof
+        ]
+    );
+
+    let foo = &tokens[1].span;
+    assert_eq!(foo.filepath, Some(path));
+    assert_eq!(foo.start, Location::new(0_usize, 4_usize));
+    assert_eq!(foo.end, Location::new(0_usize, 6_usize));
+    assert_eq!(tokens[2].span.start, Location::new(1_usize, 2_usize));
+    assert_eq!(tokens[2].span.end, Location::new(1_usize, 6_usize));
+}
+
+#[test]
+fn lex_blank_source() {
+    let tokens = lex(Path::new("a.c"), " \t\n\n".lines()).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token, Token::Eof);
+    assert_eq!(tokens[0].span.start, Location::new(1_usize, 0_usize));
+}
+
+#[test]
+fn lex_ends_with_eof() {
+    let tokens = lex(Path::new("a.c"), "".lines()).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token, Token::Eof);
+    let tokens = lex(Path::new("a.c"), "int a;\nab".lines()).unwrap();
+    let eof = tokens.last().unwrap();
+    assert_eq!(eof.token, Token::Eof);
+    assert_eq!(eof.span.start, Location::new(1_usize, 2_usize));
+}
+
+#[test]
+fn lex_strings() {
+    let tokens = lex(Path::new("a.c"), "\"hello\" \"\" x \"a b\"".lines()).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Literal(Literal::String("hello".into(), Encoding::Plain)),
+            Token::Literal(Literal::String(String::new(), Encoding::Plain)),
+            Token::Symbol("x".into()),
+            Token::Literal(Literal::String("a b".into(), Encoding::Plain)),
+            Token::Eof,
+        ]
+    );
+}
+
+fn lex_literal(source: &str) -> Result<Literal, LexError<'static>> {
+    let mut tokens = lex(Path::new("a.c"), source.lines())?;
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens.pop().unwrap().token, Token::Eof);
+    match tokens.pop().unwrap().token {
+        Token::Literal(literal) => Ok(literal),
+        token => panic!("{token:?} is not a literal"),
+    }
+}
+
+#[test]
+fn lex_integers() {
+    assert_eq!(lex_literal("42"), Ok(Literal::Int(42, IntSuffix::Int)));
+    assert_eq!(lex_literal("0"), Ok(Literal::Int(0, IntSuffix::Int)));
+    assert_eq!(lex_literal("0x1F"), Ok(Literal::Int(31, IntSuffix::Int)));
+    assert_eq!(lex_literal("0XfF"), Ok(Literal::Int(255, IntSuffix::Int)));
+    assert_eq!(lex_literal("017"), Ok(Literal::Int(15, IntSuffix::Int)));
+    assert_eq!(lex_literal("0b101"), Ok(Literal::Int(5, IntSuffix::Int)));
+}
+
+#[test]
+fn lex_integer_suffixes() {
+    assert_eq!(lex_literal("42u"), Ok(Literal::Int(42, IntSuffix::UnsignedInt)));
+    assert_eq!(lex_literal("42ULL"), Ok(Literal::Int(42, IntSuffix::UnsignedLongLong)));
+    assert_eq!(lex_literal("5L"), Ok(Literal::Int(5, IntSuffix::Long)));
+    assert_eq!(lex_literal("0x1Flu"), Ok(Literal::Int(31, IntSuffix::UnsignedLong)));
+    assert_eq!(lex_literal("0u"), Ok(Literal::Int(0, IntSuffix::UnsignedInt)));
+    assert_eq!(
+        lex_literal("42lul").unwrap_err().message,
+        "invalid suffix `lul` on integer literal `42lul`"
+    );
+    assert!(lex_literal("42lL").is_err());
+    assert_eq!(lex_literal("3ULL").unwrap().to_string(), "3ull");
+}
+
+#[test]
+fn lex_floats() {
+    assert_eq!(lex_literal("3.5"), Ok(Literal::Float(3.5, FloatSuffix::Double)));
+    assert_eq!(lex_literal(".5"), Ok(Literal::Float(0.5, FloatSuffix::Double)));
+    assert_eq!(lex_literal(".0"), Ok(Literal::Float(0.0, FloatSuffix::Double)));
+    assert_eq!(lex_literal("1e3"), Ok(Literal::Float(1000.0, FloatSuffix::Double)));
+    assert_eq!(lex_literal("2.5E-1"), Ok(Literal::Float(0.25, FloatSuffix::Double)));
+    assert_eq!(lex_literal("1."), Ok(Literal::Float(1.0, FloatSuffix::Double)));
+    assert_eq!(lex_literal("1.e3"), Ok(Literal::Float(1000.0, FloatSuffix::Double)));
+    let tokens = lex(Path::new("a.c"), "-0.0".lines()).unwrap();
+    assert_eq!(tokens[0].token, Token::Operator(Operator::Minus));
+    assert_eq!(tokens[1].token, Token::Literal(Literal::Float(0.0, FloatSuffix::Double)));
+}
+
+#[test]
+fn lex_signs_after_numbers() {
+    let tokens = lex(Path::new("a.c"), "1-2 a=1+b".lines()).unwrap();
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(tokens, ["1", "-", "2", "a", "=", "1", "+", "b", "<eof>"]);
+    assert_eq!(lex_literal("1e-5"), Ok(Literal::Float(1e-5, FloatSuffix::Double)));
+    assert_eq!(lex_literal("2E+3"), Ok(Literal::Float(2000.0, FloatSuffix::Double)));
+}
+
+#[test]
+fn lex_hex_floats() {
+    assert_eq!(lex_literal("0x1.8p3"), Ok(Literal::Float(12.0, FloatSuffix::Double)));
+    assert_eq!(lex_literal("0X.8P1"), Ok(Literal::Float(1.0, FloatSuffix::Double)));
+    assert_eq!(lex_literal("0xAp-2f"), Ok(Literal::Float(2.5, FloatSuffix::Float)));
+    assert_eq!(
+        lex_literal("0x1.8").unwrap_err().message,
+        "hexadecimal floating-point literal `0x1.8` has no exponent"
+    );
+    assert!(lex_literal("0x.p1").is_err());
+    assert!(lex_literal("0x1pz").is_err());
+}
+
+#[test]
+fn lex_digit_separators() {
+    assert_eq!(lex_literal("1'000"), Ok(Literal::Int(1000, IntSuffix::Int)));
+    assert_eq!(lex_literal("1'000'000u"), Ok(Literal::Int(1_000_000, IntSuffix::UnsignedInt)));
+    assert_eq!(lex_literal("0xDE'AD"), Ok(Literal::Int(0xDEAD, IntSuffix::Int)));
+    assert_eq!(lex_literal("1'0.5"), Ok(Literal::Float(10.5, FloatSuffix::Double)));
+    assert_eq!(
+        lex_literal("1''0").unwrap_err().message,
+        "invalid digit separator in number literal `1''0`"
+    );
+    assert!(lex_literal("1'").is_err());
+    assert!(lex_literal("0x'FF").is_err());
+    assert!(lex_literal("1'.5").is_err());
+}
+
+#[test]
+fn lex_float_suffixes() {
+    let float: f64 = "3.14".parse().unwrap();
+    assert_eq!(lex_literal("3.14f"), Ok(Literal::Float(float, FloatSuffix::Float)));
+    assert_eq!(lex_literal("2.0L"), Ok(Literal::Float(2.0, FloatSuffix::LongDouble)));
+    assert_eq!(lex_literal("1.0e3f"), Ok(Literal::Float(1000.0, FloatSuffix::Float)));
+    assert_eq!(lex_literal("1.0e3f").unwrap().to_string(), "1e3f");
+    assert!(lex_literal("1f").is_err());
+    assert!(lex_literal("1.0u").is_err());
+    assert!(lex_literal("1.0fl").is_err());
+}
+
+#[test]
+fn lex_malformed_numbers() {
+    assert!(lex_literal("1.2.3").is_err());
+    assert!(lex_literal("09").is_err());
+    assert!(lex_literal("0x").is_err());
+    assert!(lex_literal("0b12").is_err());
+    assert!(lex_literal("12abc").is_err());
+    assert!(lex_literal("99999999999999999999").is_err());
+}
+
+#[test]
+fn lex_budget() {
+    let source = "abc ".repeat(10_000);
+    let path = Path::new("a.c");
+    let error = lex_with_budget(path, source.lines(), 100).unwrap_err();
+    assert_eq!(error.message, "lexing budget exceeded");
+    assert_eq!(error.kind, LexErrorKind::BudgetExceeded);
+    assert_eq!(error.span.start, Location::new(0_usize, 100_usize));
+    assert_eq!(lex_with_budget(path, "abc def".lines(), 7).unwrap().len(), 3);
+}
+
+#[test]
+fn lex_operators() {
+    let tokens = lex(Path::new("a.c"), "a + b == c <<= 2\n".lines()).unwrap();
+    let operators: Vec<_> = tokens
+        .iter()
+        .filter_map(|token| match token.token {
+            Token::Operator(operator) =>
+                Some((operator, token.span.start.col(), token.span.end.col())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        operators,
+        [
+            (Operator::Plus, 2, 2),
+            (Operator::Equal, 6, 7),
+            (Operator::ShiftLeftAssign, 11, 13)
+        ]
+    );
+}
+
+#[test]
+fn lex_adjacent_operators() {
+    let tokens = lex(Path::new("a.c"), "x+++y->z".lines()).unwrap();
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(tokens, ["x", "++", "+", "y", "->", "z", "<eof>"]);
+}
+
+#[test]
+fn lex_final_operator() {
+    for (source, operator) in [
+        ("a;", Operator::SemiColon),
+        ("1 ==", Operator::Equal),
+        ("a <<=", Operator::ShiftLeftAssign),
+    ] {
+        let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+        assert_eq!(
+            tokens.iter().nth_back(1).map(|token| &token.token),
+            Some(&Token::Operator(operator))
+        );
+    }
+}
+
+#[test]
+fn extract_string_literals() {
+    let tokens = lex(Path::new("a.c"), "puts(\"hello\");\nputs(\"world\");".lines()).unwrap();
+    let strings: Vec<_> = string_literals(&tokens)
+        .into_iter()
+        .map(|(text, span)| (text, span.start.clone()))
+        .collect();
+    assert_eq!(
+        strings,
+        [
+            ("hello", Location::new(0_usize, 5_usize)),
+            ("world", Location::new(1_usize, 5_usize))
+        ]
+    );
+}
+
+#[test]
+fn lex_char_escapes() {
+    for (source, ch) in [
+        (r"'\n'", '\n'),
+        (r"'\t'", '\t'),
+        (r"'\r'", '\r'),
+        (r"'\\'", '\\'),
+        (r"'\''", '\''),
+        (r#"'\"'"#, '"'),
+        (r"'\0'", '\0'),
+        (r"'\a'", '\x07'),
+        (r"'\b'", '\x08'),
+        (r"'\f'", '\x0C'),
+        (r"'\v'", '\x0B'),
+        (r"'\012'", '\n'),
+        (r"'\x41'", 'A'),
+        (r"'\101'", 'A'),
+        (r"'\x7f'", '\x7F'),
+        (r"'\u00E9'", 'é'),
+        (r"'\U0001F600'", '😀'),
+    ] {
+        assert_eq!(lex_literal(source), Ok(Literal::Char(ch, Encoding::Plain)), "{source}");
+    }
+    let error = lex_literal(r" '\q'").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:3: invalid escape sequence `\\q`");
+    let error = lex_literal(r"'\x'").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:2: invalid escape sequence `\\x`");
+    assert_eq!(lex_literal(r"'\0123'"), Ok(Literal::Int(0x0A33, IntSuffix::Int)));
+    let error = lex_literal(r"'\u12'").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:2: invalid escape sequence `\\u12`");
+    assert!(lex_literal(r"'\uD800'").is_err());
+}
+
+#[test]
+fn lex_escape_ranges() {
+    assert!(lex_literal(r#""\777""#).is_err());
+    assert!(lex_literal(r"'\x100'").is_err());
+    assert!(lex_literal(r#"u8"\x100""#).is_err());
+    assert!(lex_literal(r#"u"\x10000""#).is_err());
+    for (source, literal) in [
+        (r"'\xFF'", Literal::Char('\u{FF}', Encoding::Plain)),
+        (r"L'\x100'", Literal::Char('\u{100}', Encoding::Wide)),
+        (r#"u"\xFFFF""#, Literal::String("\u{FFFF}".into(), Encoding::Utf16)),
+        (r#"U"\777""#, Literal::String("\u{1FF}".into(), Encoding::Utf32)),
+        (r#""\u00E9""#, Literal::String("é".into(), Encoding::Plain)),
+    ] {
+        assert_eq!(lex_literal(source), Ok(literal), "{source}");
+    }
+}
+
+#[test]
+fn lex_multichar_constant() {
+    assert_eq!(lex_literal("'AB'"), Ok(Literal::Int(0x4142, IntSuffix::Int)));
+    assert_eq!(lex_literal(r"'\tA'"), Ok(Literal::Int(0x0941, IntSuffix::Int)));
+    let error = lex_literal("L'AB'").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:1: more than one element in char");
+    assert!(lex_literal("'éa'").is_err());
+}
+
+#[test]
+fn lex_string_escapes() {
+    assert_eq!(
+        lex_literal(r#""line1\nline2\"""#),
+        Ok(Literal::String("line1\nline2\"".into(), Encoding::Plain))
+    );
+    assert_eq!(lex_literal(r#""a\\" "#), Ok(Literal::String("a\\".into(), Encoding::Plain)));
+    assert!(lex_literal(r#""\q""#).is_err());
+}
+
+#[test]
+fn lex_numeric_string_escapes() {
+    for (source, string) in [
+        (r#""\012""#, "\n"),
+        (r#""\x41""#, "A"),
+        (r#""\0""#, "\0"),
+        (r#""\1234""#, "S4"),
+        (r#""\x4g""#, "\x04g"),
+        (r#""\7\x7F""#, "\x07\x7F"),
+    ] {
+        assert_eq!(
+            lex_literal(source),
+            Ok(Literal::String(string.into(), Encoding::Plain)),
+            "{source}"
+        );
+    }
+    let error = lex_literal(r#""a\xg""#).unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:3: invalid escape sequence `\\x`");
+}
+
+#[test]
+fn lex_leading_dot_number() {
+    let tokens = lex(Path::new("a.c"), "x = .5f;\ns.a-.25e1".lines()).unwrap();
+    let tokens: Vec<_> = tokens
+        .into_iter()
+        .map(|token| (token.span.start, token.token))
+        .collect();
+    assert_eq!(
+        tokens[2],
+        (Location::new(0_usize, 4_usize), Token::Literal(lex_literal(".5f").unwrap()))
+    );
+    let names: Vec<_> = tokens.iter().map(|(_, token)| token.to_string()).collect();
+    assert_eq!(names[4..], ["s", ".", "a", "-", "2.5e0", "<eof>"]);
+}
+
+#[test]
+fn lex_embedded_nul() {
+    let Ok(Literal::String(string, _)) = lex_literal(r#""a\0b""#) else {
+        panic!("not a string literal");
+    };
+    assert_eq!(string.len(), 3);
+    assert_eq!(string, "a\0b");
+}
+
+#[test]
+fn lex_line_comment() {
+    let tokens = lex(Path::new("a.c"), "a = 1; // b = 2;\nc/=2".lines()).unwrap();
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(tokens, ["a", "=", "1", ";", "c", "/=", "2", "<eof>"]);
+}
+
+#[test]
+fn lex_block_comment() {
+    let source = "a /* b\n * c **/ d\ne=/**/f";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    let names: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(names, ["a", "d", "e", "=", "f", "<eof>"]);
+    assert_eq!(tokens[1].span.start, Location::new(1_usize, 9_usize));
+}
+
+#[test]
+fn lex_unterminated_block_comment() {
+    let error = lex(Path::new("a.c"), "a\n  /* b\n c".lines()).unwrap_err();
+    assert_eq!(error.to_string(), "a.c:2:3: unterminated block comment");
+}
+
+#[test]
+fn lex_if_directive() {
+    let tokens = lex(Path::new("a.c"), "#if defined(X) && Y > 2\nint a;".lines()).unwrap();
+    let directive: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.preprocessor)
+        .map(|token| token.token.to_string())
+        .collect();
+    assert_eq!(directive, ["#if", "defined", "(", "X", ")", "&&", "Y", ">", "2"]);
+    assert_eq!(tokens[1].token, Token::Operator(Operator::Defined));
+    assert_eq!(tokens[0].span.end, Location::new(0_usize, 2_usize));
+    assert_eq!(tokens.iter().filter(|token| !token.preprocessor).count(), 4);
+
+    let tokens = lex(Path::new("a.c"), "  # elif 1".lines()).unwrap();
+    assert_eq!(tokens[0].token, Token::Directive("elif".into()));
+
+    let tokens = lex(Path::new("a.c"), "#if 1 \\\n  && 2\nint a;".lines()).unwrap();
+    let directive: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.preprocessor)
+        .map(|token| token.token.to_string())
+        .collect();
+    assert_eq!(directive, ["#if", "1", "&&", "2"]);
+    assert_eq!(tokens[2].span.start, Location::new(1_usize, 2_usize));
+
+    let tokens = lex(Path::new("a.c"), "#define MAX(a, b) \\\r\n  a > b\nint".lines()).unwrap();
+    assert_eq!(tokens.iter().filter(|token| token.preprocessor).count(), 10);
+    assert!(!tokens[10].preprocessor);
+}
+
+#[test]
+fn lex_other_directives() {
+    let source = "#include <a.h>\n#pragma once\n#ifdef X\nint defined;\n#endif";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    let directives: Vec<_> = tokens
+        .iter()
+        .filter_map(|token| match &token.token {
+            Token::Directive(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(directives, ["include", "pragma", "ifdef", "endif"]);
+    assert_eq!(tokens.iter().filter(|token| token.preprocessor).count(), 11);
+    assert_eq!(tokens[9].token, Token::Symbol("X".into()));
+    assert_eq!(tokens[11].token, Token::Symbol("defined".into()));
+}
+
+#[test]
+fn lex_hash_operators() {
+    let source = "#define STR(a) #a ## 1\na # b";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(
+        tokens,
+        [
+            "#define", "STR", "(", "a", ")", "#", "a", "##", "1", "a", "#", "b", "<eof>"
+        ]
+    );
+}
+
+#[test]
+fn lex_dots() {
+    // `..` isn't a C operator, but lexes as two `.`, like GCC does
+    for (source, operators) in [
+        ("f(int, ...)", &["(", ",", "...", ")"][..]),
+        ("a..b", &[".", "."]),
+        ("....", &["...", "."]),
+        ("..", &[".", "."]),
+    ] {
+        let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+        let spellings: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| match &token.token {
+                Token::Operator(operator) => Some(operator.spelling()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(spellings, operators, "{source}");
+    }
+    let tokens = lex(Path::new("a.c"), "...".lines()).unwrap();
+    assert_eq!(tokens[0].token, Token::Operator(Operator::Ellipsis));
+    assert_eq!(tokens[0].span.end, Location::new(0_usize, 2_usize));
+}
+
+#[test]
+fn lex_double_interrogation() {
+    // Without trigraphs, `??` is two ternary operators, invalid only to a parser
+    let tokens = lex(Path::new("a.c"), "a??b".lines()).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Symbol("a".into()),
+            Token::Operator(Operator::Interrogation),
+            Token::Operator(Operator::Interrogation),
+            Token::Symbol("b".into()),
+            Token::Eof
+        ]
+    );
+}
+
+#[test]
+fn lex_defined_operator() {
+    let tokens = lex(Path::new("a.c"), "#elif defined(FOO)\ndefined".lines()).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Directive("elif".into()),
+            Token::Operator(Operator::Defined),
+            Token::Operator(Operator::ParenthesisOpen),
+            Token::Symbol("FOO".into()),
+            Token::Operator(Operator::ParenthesisClose),
+            Token::Symbol("defined".into()),
+            Token::Eof,
+        ]
+    );
+}
+
+#[test]
+fn lex_error_span() {
+    let path = Path::new("a.c");
+    let error = lex(path, "char c =\n  '';".lines()).unwrap_err();
+    assert_eq!(error.message, "missing element in char");
+    assert_eq!(error.kind, LexErrorKind::Syntax);
+    assert_eq!(error.span.filepath, Some(path));
+    assert_eq!(error.span.start, Location::new(1_usize, 2_usize));
+    assert_eq!(error.span.end, Location::new(1_usize, 3_usize));
+    assert_eq!(error.to_string(), "a.c:2:3: missing element in char");
+}
+
+#[test]
+fn lex_recovering_from_errors() {
+    let (tokens, errors) = lex_recovering(Path::new("a.c"), "a = 1.2.3; x\nb $b; c".lines());
+    let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "a.c:1:5: invalid floating-point literal `1.2.3`",
+            "a.c:2:3: invalid character"
+        ]
+    );
+    let tokens: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(tokens, ["a", "=", "<error>", "x", "b", "<error>", "c", "<eof>"]);
+}
+
+#[test]
+fn spans_cover_tokens() {
+    let tokens = lex(Path::new("a.c"), "foobar \"a b\" '\\n' <<= 12".lines()).unwrap();
+    let spans: Vec<_> = tokens
+        .iter()
+        .map(|token| (token.span.start.col(), token.span.end.col()))
+        .collect();
+    assert_eq!(spans, [(0, 5), (7, 11), (13, 16), (18, 20), (22, 23), (24, 24)]);
+}
+
+#[test]
+fn lex_column_units() {
+    let source = "\"é😀\" x";
+    let column = |unit| {
+        let tokens = lex_with_columns(Path::new("a.c"), source.lines(), unit).unwrap();
+        (tokens[0].span.end.col(), tokens[1].span.start.col())
+    };
+    assert_eq!(column(ColumnUnit::Chars), (3, 5));
+    assert_eq!(column(ColumnUnit::Bytes), (7, 9));
+    assert_eq!(column(ColumnUnit::Utf16), (4, 6));
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    assert_eq!(tokens[1].span.start.col(), 5);
+}
+
+#[test]
+fn lex_line_endings() {
+    let spans = |source: &str| {
+        let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+        tokens
+            .iter()
+            .map(|token| (token.span.start.human(), token.span.end.human()))
+            .collect::<Vec<_>>()
+    };
+    let expected = spans("int x;\n\nfoo(1)\n");
+    assert_eq!(expected[3], ((3, 1), (3, 3)));
+    assert_eq!(spans("int x;\r\n\r\nfoo(1)\r\n"), expected);
+    assert_eq!(spans("int x;\r\rfoo(1)\r"), expected);
+    assert_eq!(spans("int x;\r\n\rfoo(1)\n"), expected);
+}
+
+#[test]
+fn lex_str_two_lines() {
+    let path = Path::new("a.c");
+    let tokens = lex_str(path, "int x;\r\nx = 1;\r\n").unwrap();
+    let texts: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(texts, ["int", "x", ";", "x", "=", "1", ";", "<eof>"]);
+    assert_eq!(tokens[3].span.start.human(), (2, 1));
+    assert_eq!(tokens[3].span.start.offset(), 8);
+    assert_eq!(tokens, lex(path, "int x;\nx = 1;".lines()).unwrap());
+}
+
+#[test]
+fn lex_file_from_disk() {
+    let path = std::env::temp_dir().join(format!("lex_file_{}.c", std::process::id()));
+    fs::write(&path, "x = 1;\n").unwrap();
+    let tokens = lex_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let texts: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(texts, ["x", "=", "1", ";", "<eof>"]);
+    assert_eq!(tokens[0].span.filepath.as_deref(), Some(path.as_path()));
+    let error = lex_file(&path).unwrap_err();
+    assert!(error.message.starts_with("cannot read file: "));
+    assert_eq!(error.kind, LexErrorKind::Io);
+    assert_eq!(error.span.filepath, Some(path.as_path()));
+}
+
+#[test]
+fn lex_keywords() {
+    let tokens = lex(Path::new("a.c"), "return returns _Bool".lines()).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Keyword(Keyword::Return),
+            Token::Symbol("returns".into()),
+            Token::Keyword(Keyword::UBool),
+            Token::Eof
+        ]
+    );
+}
+
+#[test]
+fn lex_keywords_of_standard() {
+    let source = "bool _Bool restrict";
+    let tokens = lex_with_standard(Path::new("a.c"), source.lines(), Standard::C89).unwrap();
+    let names: Vec<_> = tokens.iter().map(|token| token.token.to_string()).collect();
+    assert_eq!(names, ["bool", "_Bool", "restrict", "<eof>"]);
+    assert!(
+        tokens[..3]
+            .iter()
+            .all(|token| matches!(token.token, Token::Symbol(_)))
+    );
+
+    let tokens = lex_with_standard(Path::new("a.c"), source.lines(), Standard::C99).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Symbol("bool".into()),
+            Token::Keyword(Keyword::UBool),
+            Token::Keyword(Keyword::Restrict),
+            Token::Eof
+        ]
+    );
+}
+
+#[test]
+fn lex_gnu_keywords() {
+    let source = "__inline__ int __attribute__ inline";
+    let tokens = lex_with_gnu(Path::new("a.c"), source.lines(), Standard::C99).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Keyword(Keyword::Inline),
+            Token::Keyword(Keyword::Int),
+            Token::Keyword(Keyword::GnuAttribute),
+            Token::Keyword(Keyword::Inline),
+            Token::Eof
+        ]
+    );
+
+    let tokens = lex_with_standard(Path::new("a.c"), source.lines(), Standard::C99).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(tokens[0], Token::Symbol("__inline__".into()));
+    assert_eq!(tokens[2], Token::Symbol("__attribute__".into()));
+}
+
+#[test]
+fn lex_generic_selection() {
+    let tokens = lex(Path::new("a.c"), "_Generic(x, int: 1, default: 0)".lines()).unwrap();
+    let tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Keyword(Keyword::UGeneric),
+            Token::Operator(Operator::ParenthesisOpen),
+            Token::Symbol("x".into()),
+            Token::Operator(Operator::Comma),
+            Token::Keyword(Keyword::Int),
+            Token::Operator(Operator::Colon),
+            Token::Literal(Literal::Int(1, IntSuffix::Int)),
+            Token::Operator(Operator::Comma),
+            Token::Keyword(Keyword::Default),
+            Token::Operator(Operator::Colon),
+            Token::Literal(Literal::Int(0, IntSuffix::Int)),
+            Token::Operator(Operator::ParenthesisClose),
+            Token::Eof
+        ]
+    );
+}
+
+#[test]
+fn valid_pp_tokens() {
+    let (tokens, _) = lex_recovering(Path::new("a.c"), "#if defined X\nint x = 'a';".lines());
+    assert!(is_valid_pp_tokens(&tokens));
+    let (tokens, _) = lex_recovering(Path::new("a.c"), "int x = $;".lines());
+    assert!(!is_valid_pp_tokens(&tokens));
+}
+
+#[test]
+fn lex_on_thread() {
+    let source = "int x;\nx = 1;\n".repeat(100);
+    let (receiver, handle) = spawn_lexer("a.c".into(), source, 4);
+    let tokens: Vec<_> = receiver.iter().collect();
+    assert_eq!(handle.join().unwrap(), Ok(()));
+    assert_eq!(tokens.len(), 701);
+    let ids: Vec<_> = tokens.iter().map(|token| token.id).collect();
+    assert_eq!(ids, (0..701).collect::<Vec<_>>());
+    assert_eq!(tokens[7].span.start.human(), (3, 1));
+    assert_eq!(tokens[700].token, Token::Eof);
+    let (receiver, handle) = spawn_lexer("a.c".into(), "x;\n$".into(), 1);
+    assert_eq!(receiver.iter().count(), 2);
+    let error = handle.join().unwrap().unwrap_err();
+    assert_eq!(error.to_string(), "a.c:2:1: invalid character");
+    assert_eq!(error.kind, LexErrorKind::Syntax);
+    assert_eq!(error.span.filepath.as_deref(), Some(Path::new("a.c")));
+}
+
+#[test]
+fn lex_prefixed_literals() {
+    assert_eq!(lex_literal("L'a'"), Ok(Literal::Char('a', Encoding::Wide)));
+    assert_eq!(lex_literal("u'a'"), Ok(Literal::Char('a', Encoding::Utf16)));
+    assert_eq!(lex_literal("U'a'"), Ok(Literal::Char('a', Encoding::Utf32)));
+    assert_eq!(lex_literal("u8'a'"), Ok(Literal::Char('a', Encoding::Utf8)));
+    assert_eq!(lex_literal("L\"ab\""), Ok(Literal::String("ab".into(), Encoding::Wide)));
+    assert_eq!(lex_literal("u\"ab\""), Ok(Literal::String("ab".into(), Encoding::Utf16)));
+    assert_eq!(lex_literal("U\"ab\""), Ok(Literal::String("ab".into(), Encoding::Utf32)));
+    assert_eq!(lex_literal("u8\"x\""), Ok(Literal::String("x".into(), Encoding::Utf8)));
+    assert_eq!(lex_literal("u8\"x\"").unwrap().to_string(), "u8\"x\"");
+    let tokens = lex(Path::new("a.c"), "L 'a' x\"b\" L'c'".lines()).unwrap();
+    let tokens: Vec<_> = tokens
+        .into_iter()
+        .map(|token| (token.span.start.col(), token.token))
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            (0, Token::Symbol("L".into())),
+            (2, Token::Literal(Literal::Char('a', Encoding::Plain))),
+            (6, Token::Symbol("x".into())),
+            (7, Token::Literal(Literal::String("b".into(), Encoding::Plain))),
+            (11, Token::Literal(Literal::Char('c', Encoding::Wide))),
+            (15, Token::Eof)
+        ]
+    );
+}
+
+#[test]
+fn concat_prefixed_strings() {
+    let strings = |source: &str| {
+        let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+        concat_adjacent_strings(tokens).map(|tokens| {
+            tokens
+                .into_iter()
+                .map(|token| token.token)
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(
+        strings("L\"a\" \"b\"\n\"c\";"),
+        Ok(vec![
+            Token::Literal(Literal::String("abc".into(), Encoding::Wide)),
+            Token::Operator(Operator::SemiColon),
+            Token::Eof
+        ])
+    );
+    assert_eq!(
+        strings("\"a\" u8\"b\"").unwrap()[0],
+        Token::Literal(Literal::String("ab".into(), Encoding::Utf8))
+    );
+    let error = strings("x = u\"a\" U\"b\";").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "a.c:1:5: cannot concatenate string literals prefixed with `u` and `U`"
+    );
+    assert_eq!(error.span.end, Location::new(0_usize, 12_usize));
+
+    let tokens = lex(Path::new("a.c"), "#include \"a\" \"b\"".lines()).unwrap();
+    assert_eq!(concat_adjacent_strings(tokens).map(|tokens| tokens.len()), Ok(4));
+}
+
+#[test]
+fn diagnostics_grouped_by_file() {
+    let (first, second) = (Path::new("a.c"), Path::new("b.c"));
+    let results = [
+        lex(first, "int $;".lines()),
+        lex(second, "x = 1;".lines()),
+        lex(second, "\n@".lines()),
+    ];
+    let diagnostics = diagnostics_by_file(results);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[first][0].to_string(), "a.c:1:5: invalid character");
+    assert_eq!(diagnostics[second].len(), 1);
+    assert_eq!(diagnostics[second][0].to_string(), "b.c:2:1: invalid character");
+}
+
+#[test]
+fn lex_unterminated_literals() {
+    let path = Path::new("a.c");
+    let error = lex_str(path, "x = \"abc").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:5: unterminated string literal");
+    assert_eq!(error.span.end, Location::new(0_usize, 4_usize));
+    let error = lex_str(path, "c = 'a").unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:5: unterminated char literal");
+    let error = lex(path, "s = \"ab\ncd\";".lines()).unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:5: unterminated string literal");
+    let error = lex(path, "c = '\n';".lines()).unwrap_err();
+    assert_eq!(error.to_string(), "a.c:1:5: unterminated char literal");
+    assert_eq!(lex_literal("\"ab\\\ncd\""), Ok(Literal::String("abcd".into(), Encoding::Plain)));
+}
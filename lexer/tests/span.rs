@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use lexer::keyword::Keyword;
+use lexer::literal::{IntSuffix, Literal};
+use lexer::location::Location;
+use lexer::token::Token;
+use lexer::{
+    LToken, TokenSpan, context_around, first_token, last_token, leading_spaces, lex, lex_str,
+    relex_token, shift_spans, source_line, split_at_location, tokens_span,
+};
+
+fn token_at(line: usize, col: usize) -> LToken<'static> {
+    let location = Location::new(line, col);
+    LToken {
+        id: 0,
+        preprocessor: false,
+        span: TokenSpan { filepath: None, start: location.clone(), end: location },
+        token: Token::Symbol("b".into()),
+    }
+}
+
+#[test]
+fn source_line_of_token() {
+    let source = "int a;\nint b;\n  b = 2;\n";
+    assert_eq!(source_line(source, &token_at(2, 2)), Some("  b = 2;"));
+    assert_eq!(source_line(source, &token_at(1, 4)), Some("int b;"));
+    assert_eq!(source_line(source, &token_at(5, 0)), None);
+
+    let source = "int x;\rfoo(1)\r\nbar";
+    let tokens = lex_str(Path::new("a.c"), source).unwrap();
+    assert_eq!(source_line(source, &tokens[3]), Some("foo(1)"));
+    assert_eq!(source_line(source, &tokens[7]), Some("bar"));
+}
+
+#[test]
+fn split_stream_at_location() {
+    let tokens: Vec<_> = [(0, 0), (0, 4), (1, 0), (1, 2), (2, 0)]
+        .into_iter()
+        .map(|(line, col)| token_at(line, col))
+        .collect();
+    let (before, after) = split_at_location(&tokens, &Location::new(1_usize, 1_usize));
+    assert_eq!(before.len(), 3);
+    assert_eq!(after.len(), 2);
+    assert_eq!(after[0].span.start, Location::new(1_usize, 2_usize));
+
+    let (before, after) = split_at_location(&tokens, &Location::new(1_usize, 2_usize));
+    assert_eq!(before.last().unwrap().span.start, Location::new(1_usize, 0_usize));
+    assert_eq!(after.len(), 2);
+
+    let (before, after) = split_at_location(&tokens, &Location::new(5_usize, 0_usize));
+    assert_eq!((before.len(), after.len()), (5, 0));
+}
+
+#[test]
+fn context_around_middle_token() {
+    let tokens: Vec<_> = (0..5).map(|col| token_at(0, col * 2)).collect();
+    let context = context_around(&tokens, &Location::new(0_usize, 4_usize), 1);
+    let cols: Vec<_> = context
+        .iter()
+        .map(|token| token.span.start.clone())
+        .collect();
+    assert_eq!(cols, [2_usize, 4, 6].map(|col| Location::new(0_usize, col)));
+
+    assert_eq!(context_around(&tokens, &Location::new(0_usize, 0_usize), 1).len(), 2);
+    assert_eq!(
+        context_around(&tokens, &Location::new(0_usize, 3_usize), 0)[0]
+            .span
+            .start,
+        Location::new(0_usize, 4_usize)
+    );
+    assert_eq!(context_around(&tokens, &Location::new(0_usize, 4_usize), 10).len(), 5);
+    assert!(context_around(&tokens, &Location::new(1_usize, 0_usize), 1).is_empty());
+}
+
+#[test]
+fn byte_len_of_multibyte_token() {
+    let source = "int a;\nchar *s = \"aéb\";\n";
+    let span = TokenSpan {
+        filepath: None,
+        start: Location::new(1_usize, 10_usize),
+        end: Location::new(1_usize, 14_usize),
+    };
+    assert_eq!(span.byte_len(source), 6);
+    let span = TokenSpan {
+        filepath: None,
+        start: Location::new(0_usize, 4_usize),
+        end: Location::new(0_usize, 4_usize),
+    };
+    assert_eq!(span.byte_len(source), 1);
+    let span = TokenSpan {
+        filepath: None,
+        start: Location::new(3_usize, 0_usize),
+        end: Location::new(3_usize, 2_usize),
+    };
+    assert_eq!(span.byte_len(source), 0);
+}
+
+#[test]
+fn shift_spans_after_edit() {
+    let mut tokens: Vec<_> = [(0, 0), (1, 2), (1, 6), (2, 0)]
+        .into_iter()
+        .map(|(line, col)| token_at(line, col))
+        .collect();
+    shift_spans(&mut tokens, &Location::new(1_usize, 2_usize), 1, -2, 3);
+    let starts: Vec<_> = tokens
+        .iter()
+        .map(|token| token.span.start.clone())
+        .collect();
+    assert_eq!(
+        starts,
+        [
+            Location::new(0_usize, 0_usize),
+            Location::new(2_usize, 0_usize),
+            Location::new(2_usize, 4_usize),
+            Location::new(3_usize, 0_usize),
+        ]
+    );
+    assert_eq!(tokens[0].span.start.offset(), 0);
+    assert_eq!(tokens[1].span.start.offset(), 3);
+
+    // Breaking the line before `=` moves the offsets of the following tokens
+    let spans = |tokens: &[LToken<'_>]| -> Vec<_> {
+        tokens
+            .iter()
+            .map(|token| &token.span)
+            .map(|span| (span.start.clone(), span.start.offset(), span.end.offset()))
+            .collect()
+    };
+    let mut tokens = lex_str(Path::new("a.c"), "int a;\nint b = 2;\nint c;").unwrap();
+    shift_spans(&mut tokens, &Location::new(1_usize, 6_usize), 1, -4, 3);
+    let edited = lex_str(Path::new("a.c"), "int a;\nint b \n  = 2;\nint c;").unwrap();
+    assert_eq!(spans(&tokens), spans(&edited));
+}
+
+#[test]
+fn leading_spaces_of_token() {
+    let source = "int a;\n    b = 2;\n\t  c  =3;\n";
+    assert_eq!(leading_spaces(source, &token_at(0, 0)), 0);
+    assert_eq!(leading_spaces(source, &token_at(1, 4)), 4);
+    assert_eq!(leading_spaces(source, &token_at(2, 3)), 3);
+    assert_eq!(leading_spaces(source, &token_at(2, 6)), 2);
+    assert_eq!(leading_spaces(source, &token_at(2, 7)), 0);
+}
+
+#[test]
+fn display_path_with_forward_slashes() {
+    let mut span = TokenSpan { filepath: Some(Path::new(r"src\lexer\a.c")), ..Default::default() };
+    assert_eq!(span.display_path(true).as_deref(), Some("src/lexer/a.c"));
+    assert_eq!(span.display_path(false).as_deref(), Some(r"src\lexer\a.c"));
+    span.filepath = Some(Path::new("src/a.c"));
+    assert_eq!(span.display_path(true).as_deref(), Some("src/a.c"));
+    span.filepath = None;
+    assert_eq!(span.display_path(true), None);
+}
+
+#[test]
+fn overall_span_of_program() {
+    let path = Path::new("a.c");
+    let tokens = lex(path, "int main() {\n    return 0;\n}\n".lines()).unwrap();
+    assert_eq!(first_token(&tokens).unwrap().token, Token::Keyword(Keyword::Int));
+    assert_eq!(last_token(&tokens).unwrap().token.to_string(), "}");
+    let span = tokens_span(&tokens).unwrap();
+    assert_eq!(span.filepath, Some(path));
+    assert_eq!((span.start.human(), span.end.human()), ((1, 1), (3, 1)));
+    let eof = lex(path, "\n".lines()).unwrap();
+    assert_eq!(tokens_span(&eof), None);
+}
+
+#[test]
+fn token_ids_are_increasing() {
+    let tokens = lex(Path::new("a.c"), "int x = 1;\n#if defined X\n".lines()).unwrap();
+    let ids: Vec<_> = tokens.iter().map(|token| token.id).collect();
+    assert_eq!(ids, (0..tokens.len()).collect::<Vec<_>>());
+}
+
+#[test]
+fn relex_edited_number() {
+    let tokens = lex(Path::new("a.c"), "int x;\nx = 1234;".lines()).unwrap();
+    let token = relex_token("int x;\nx = 1294;", &tokens[5]).unwrap();
+    assert_eq!(token.token, Token::Literal(Literal::Int(1294, IntSuffix::Int)));
+    assert_eq!((token.id, &token.span), (tokens[5].id, &tokens[5].span));
+    let error = relex_token("int x;\nx = 12345;", &tokens[5]).unwrap_err();
+    assert_eq!(error.message, "the edit changed the boundaries of the token");
+    let error = relex_token("int x;\nx = 12 4;", &tokens[5]).unwrap_err();
+    assert_eq!(error.message, "the edit changed the boundaries of the token");
+    let error = relex_token("int x;\nx = 12a4;", &tokens[5]).unwrap_err();
+    assert_eq!(error.to_string(), "a.c:2:5: invalid integer literal `12a4`");
+
+    let source = "int x;\nx = 1234; /* start\n end */";
+    let tokens = lex(Path::new("a.c"), source.lines()).unwrap();
+    let token = relex_token("int x;\nx = 1294; /* start\n end */", &tokens[5]).unwrap();
+    assert_eq!(token.token, Token::Literal(Literal::Int(1294, IntSuffix::Int)));
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use lexer::lex_with_symbols;
+use lexer::location::Location;
+use lexer::symbol::{SymbolTable, SymbolTableEntry};
+
+fn entry(name: &str, scope: &str, line: usize) -> SymbolTableEntry {
+    SymbolTableEntry::new(name.into(), "int".into(), scope.into(), Location::new(line, 4_usize))
+}
+
+#[test]
+fn redeclaration_reports_both_locations() {
+    let mut table = SymbolTable::default();
+    table.declare(entry("a", "main", 1)).unwrap();
+    let error = table.declare(entry("a", "main", 3)).unwrap_err();
+    assert_eq!(error.name, "a");
+    assert_eq!(error.first, Location::new(1_usize, 4_usize));
+    assert_eq!(error.second, Location::new(3_usize, 4_usize));
+    assert_eq!(table.get("main", "a").unwrap().location(), &Location::new(1_usize, 4_usize));
+}
+
+#[test]
+fn same_name_in_other_scope() {
+    let mut table = SymbolTable::default();
+    table.declare(entry("a", "main", 1)).unwrap();
+    table.declare(entry("a", "foo", 5)).unwrap();
+    table.declare(entry("b", "main", 2)).unwrap();
+    assert!(table.get("foo", "a").is_some());
+    assert!(table.get("foo", "b").is_none());
+}
+
+#[test]
+fn lex_with_seeded_symbols() {
+    let mut table = SymbolTable::default();
+    table
+        .declare(entry("__STDC__", SymbolTable::FILE_SCOPE, 0))
+        .unwrap();
+    let source = "int a = __STDC__;\n__STDC__";
+    let tokens = lex_with_symbols(Path::new("a.c"), source.lines(), &mut table).unwrap();
+    assert_eq!(tokens.len(), 7);
+    let references = table
+        .get(SymbolTable::FILE_SCOPE, "__STDC__")
+        .unwrap()
+        .references();
+    assert_eq!(
+        references,
+        [
+            Location::new(0_usize, 8_usize),
+            Location::new(1_usize, 0_usize)
+        ]
+    );
+    assert!(table.get(SymbolTable::FILE_SCOPE, "a").is_none());
+}